@@ -11,27 +11,47 @@ use criterion::{
 use fft::{
     allocators::{array::ArrayAllocator, boxed::BoxedAllocator},
     implementations::{CooleyTukey, Naive},
-    windows::{hanning::Hanning, Rect},
+    windows::{
+        bartlett::Bartlett, bartlett_hann::BartlettHann, blackman_harris::BlackmanHarris,
+        cosine::Cosine, gaussian::Gaussian, hanning::Hanning, tukey::Tukey, welch::Welch, Rect,
+    },
     Allocator, ImgUnit, Implementation, Scalar, WindowFunction,
 };
-use num_complex::{Complex32, ComplexFloat};
+use num_complex::{Complex32, Complex64, ComplexFloat};
 use rand::{distributions::Standard, prelude::Distribution, rngs::StdRng, Rng, SeedableRng};
 
 pub fn criterion_benchmark(c: &mut Criterion) {
     run_cooley_bench_group::<Complex32, Rect>(c, "FFT<Complex32>");
     run_cooley_bench_group::<Complex32, Hanning>(c, "FFT<Complex32> + Hanning");
+    run_cooley_bench_group::<Complex64, Rect>(c, "FFT<Complex64>");
+    run_cooley_in_place_bench_group::<Complex32, Rect>(c, "FFT<Complex32> in place");
+    run_cooley_in_place_bench_group::<Complex32, Hanning>(c, "FFT<Complex32> + Hanning in place");
+    run_window_bench_group::<Complex32, Hanning>(c, "Hanning<Complex32> table vs on the fly");
+    run_window_bench_group::<Complex32, BlackmanHarris>(
+        c,
+        "BlackmanHarris<Complex32> table vs on the fly",
+    );
+    run_window_bench_group::<Complex32, Tukey>(c, "Tukey<Complex32> table vs on the fly");
+    run_window_bench_group::<Complex32, Gaussian>(c, "Gaussian<Complex32> table vs on the fly");
+    run_window_bench_group::<Complex32, Bartlett>(c, "Bartlett<Complex32> table vs on the fly");
+    run_window_bench_group::<Complex32, Welch>(c, "Welch<Complex32> table vs on the fly");
+    run_window_bench_group::<Complex32, Cosine>(c, "Cosine<Complex32> table vs on the fly");
+    run_window_bench_group::<Complex32, BartlettHann>(
+        c,
+        "BartlettHann<Complex32> table vs on the fly",
+    );
     run_naive_bench_group::<Complex32, Rect>(c, "Naive<Complex32>");
     run_naive_bench_group::<Complex32, Hanning>(c, "Naive<Complex32> + Hanning");
 }
 
 fn run_cooley_bench_group<T, W>(c: &mut Criterion, name: &'static str)
 where
-    W: WindowFunction<T>,
+    W: WindowFunction<T> + Default,
     T: Copy
         + Debug
         + Default
         + Add<Output = T>
-        + Mul<Scalar, Output = T>
+        + Mul<Scalar<T>, Output = T>
         + Sub<Output = T>
         + ComplexFloat
         + ImgUnit,
@@ -49,15 +69,61 @@ where
     group.finish();
 }
 
+fn run_cooley_in_place_bench_group<T, W>(c: &mut Criterion, name: &'static str)
+where
+    W: WindowFunction<T> + Default,
+    T: Copy
+        + Debug
+        + Default
+        + Add<Output = T>
+        + Mul<Scalar<T>, Output = T>
+        + Sub<Output = T>
+        + ComplexFloat
+        + ImgUnit,
+    [T]: Randomizable<T>,
+{
+    let mut group = c.benchmark_group(name);
+    run_in_place_bench::<T, 1_048_576, CooleyTukey, W, BoxedAllocator>(&mut group);
+    run_in_place_bench::<T, 65_536, CooleyTukey, W, BoxedAllocator>(&mut group);
+    run_in_place_bench::<T, 1_024, CooleyTukey, W, BoxedAllocator>(&mut group);
+    run_in_place_bench::<T, 1_024, CooleyTukey, W, ArrayAllocator>(&mut group);
+    run_in_place_bench::<T, 512, CooleyTukey, W, BoxedAllocator>(&mut group);
+    run_in_place_bench::<T, 512, CooleyTukey, W, ArrayAllocator>(&mut group);
+    run_in_place_bench::<T, 32, CooleyTukey, W, BoxedAllocator>(&mut group);
+    run_in_place_bench::<T, 32, CooleyTukey, W, ArrayAllocator>(&mut group);
+    group.finish();
+}
+
+fn run_window_bench_group<T, W>(c: &mut Criterion, name: &'static str)
+where
+    W: WindowFunction<T> + Default,
+    T: Copy
+        + Debug
+        + Default
+        + Add<Output = T>
+        + Mul<Scalar<T>, Output = T>
+        + Sub<Output = T>
+        + ComplexFloat
+        + ImgUnit,
+    [T]: Randomizable<T>,
+{
+    let mut group = c.benchmark_group(name);
+    run_window_bench::<T, 65_536, W, BoxedAllocator>(&mut group);
+    run_window_bench::<T, 1_024, W, BoxedAllocator>(&mut group);
+    run_window_bench::<T, 1_024, W, ArrayAllocator>(&mut group);
+    run_window_bench::<T, 32, W, ArrayAllocator>(&mut group);
+    group.finish();
+}
+
 fn run_naive_bench_group<T, W>(c: &mut Criterion, name: &'static str)
 where
-    W: WindowFunction<T>,
+    W: WindowFunction<T> + Default,
     T: Copy
         + Debug
         + Default
         + Add<Output = T>
         + Sub<Output = T>
-        + Mul<Scalar, Output = T>
+        + Mul<Scalar<T>, Output = T>
         + ComplexFloat
         + ImgUnit,
     [T]: Randomizable<T>,
@@ -74,8 +140,8 @@ fn run_bench<T, const N: usize, I, W, A>(c: &mut BenchmarkGroup<'_, WallTime>)
 where
     I: Implementation<T, N, A>,
     A: Allocator<T, N>,
-    W: WindowFunction<T>,
-    T: Copy + Debug + Default,
+    W: WindowFunction<T> + Default,
+    T: Copy + Debug + Default + ComplexFloat + Mul<Scalar<T>, Output = T>,
     [T]: Randomizable<T>,
 {
     let allocator_name = std::any::type_name::<A>().split("::").last().unwrap();
@@ -95,6 +161,69 @@ where
     );
 }
 
+fn run_in_place_bench<T, const N: usize, I, W, A>(c: &mut BenchmarkGroup<'_, WallTime>)
+where
+    I: Implementation<T, N, A>,
+    A: Allocator<T, N>,
+    W: WindowFunction<T> + Default,
+    T: Copy + Debug + Default + ComplexFloat + Mul<Scalar<T>, Output = T>,
+    [T]: Randomizable<T>,
+{
+    let allocator_name = std::any::type_name::<A>().split("::").last().unwrap();
+    let strategy_name = std::any::type_name::<I>().split("::").last().unwrap();
+    let vec = generate::<T, N>();
+    let mut buffer = A::allocate();
+    let engine = fft::Engine::<T, N, I, W, A>::new();
+    c.bench_with_input(
+        BenchmarkId::new(format!("{strategy_name}_{allocator_name}").as_str(), N),
+        &N,
+        |b, _| {
+            b.iter(|| {
+                buffer.as_mut().copy_from_slice(vec.as_slice());
+                engine.fft_in_place(black_box(buffer.as_mut()))
+            })
+        },
+    );
+}
+
+/// Engine windowing from its precomputed table vs evaluating the window on every frame.
+fn run_window_bench<T, const N: usize, W, A>(c: &mut BenchmarkGroup<'_, WallTime>)
+where
+    A: Allocator<T, N>,
+    CooleyTukey: Implementation<T, N, A>,
+    W: WindowFunction<T> + Default,
+    T: Copy + Debug + Default + ComplexFloat + Mul<Scalar<T>, Output = T>,
+    [T]: Randomizable<T>,
+{
+    let allocator_name = std::any::type_name::<A>().split("::").last().unwrap();
+    let vec = generate::<T, N>();
+    let mut buffer = A::allocate();
+    let window = W::default();
+    let table_engine = fft::Engine::<T, N, CooleyTukey, W, A>::new();
+    let rect_engine = fft::Engine::<T, N, CooleyTukey, Rect, A>::new();
+    c.bench_with_input(
+        BenchmarkId::new(format!("table_{allocator_name}").as_str(), N),
+        &N,
+        |b, _| {
+            b.iter(|| {
+                buffer.as_mut().copy_from_slice(vec.as_slice());
+                table_engine.fft_in_place(black_box(buffer.as_mut()))
+            })
+        },
+    );
+    c.bench_with_input(
+        BenchmarkId::new(format!("on_the_fly_{allocator_name}").as_str(), N),
+        &N,
+        |b, _| {
+            b.iter(|| {
+                buffer.as_mut().copy_from_slice(vec.as_slice());
+                window.window_in_place(black_box(buffer.as_mut()));
+                rect_engine.fft_in_place(buffer.as_mut())
+            })
+        },
+    );
+}
+
 pub trait Randomizable<T> {
     fn randomize(&mut self, seed: u64);
 }
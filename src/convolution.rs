@@ -0,0 +1,389 @@
+use core::{
+    iter,
+    ops::{Add, Mul, Range, Sub},
+};
+
+use alloc::vec::Vec;
+use num_complex::Complex;
+use num_traits::{Float, FloatConst};
+
+use crate::{
+    implementations::CooleyTukey, scalar, windows::Rect, ComplexFloat, DynEngine, ImgUnit, Scalar,
+    Scaling,
+};
+
+/// Part of the linear convolution (or correlation) of `a` and `b` that is returned.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Mode {
+    /// Every output sample, `a.len() + b.len() - 1` of them.
+    #[default]
+    Full,
+    /// The `a.len()` samples at the center of the full output.
+    Same,
+    /// The `max - min + 1` samples computed without zero padding, where the shorter input
+    /// overlaps the longer one entirely.
+    Valid,
+}
+
+impl Mode {
+    /// Range of the full output of inputs of `a` and `b` samples kept by the mode.
+    fn range(self, a: usize, b: usize) -> Range<usize> {
+        match self {
+            Mode::Full => 0..a + b - 1,
+            Mode::Same => {
+                let start = (b - 1) / 2;
+                start..start + a
+            }
+            Mode::Valid => a.min(b) - 1..a.max(b),
+        }
+    }
+}
+
+/// Linear convolution of `a` and `b`, through transforms zero-padded to the next power of
+/// two of the full output length. Returns an empty output if either input is empty.
+pub fn convolve<T>(a: &[T], b: &[T], mode: Mode) -> Vec<T>
+where
+    T: Copy
+        + Add<Output = T>
+        + Sub<Output = T>
+        + Mul<Scalar<T>, Output = T>
+        + ImgUnit
+        + ComplexFloat,
+{
+    if a.is_empty() || b.is_empty() {
+        return Vec::new();
+    }
+    let size = (a.len() + b.len() - 1).next_power_of_two();
+    let engine = engine::<T>(size);
+    let mut product = spectrum(&engine, a.iter().copied());
+    for (x, y) in product.iter_mut().zip(spectrum(&engine, b.iter().copied())) {
+        *x = *x * y;
+    }
+    inverse(&engine, &product, mode.range(a.len(), b.len()))
+}
+
+/// Cross-correlation `z[k] = Σ a[n + k - (b.len() - 1)] conj(b[n])` of `a` and `b`: in the
+/// full output, lag 0 is at index `b.len() - 1` and `a` lagging `b` by `d` samples peaks at
+/// `b.len() - 1 + d`. Modes select the same parts as for [`convolve`].
+pub fn correlate<T>(a: &[T], b: &[T], mode: Mode) -> Vec<T>
+where
+    T: Copy
+        + Add<Output = T>
+        + Sub<Output = T>
+        + Mul<Scalar<T>, Output = T>
+        + ImgUnit
+        + ComplexFloat,
+{
+    let reversed = b.iter().rev().map(|x| x.conj()).collect::<Vec<_>>();
+    convolve(a, &reversed, mode)
+}
+
+/// Autocorrelation of `x`, [`correlate`] of `x` with itself (lag 0 at index `x.len() - 1`
+/// of the full output) from a single forward transform.
+pub fn autocorrelate<T>(x: &[T], mode: Mode) -> Vec<T>
+where
+    T: Copy
+        + Add<Output = T>
+        + Sub<Output = T>
+        + Mul<Scalar<T>, Output = T>
+        + ImgUnit
+        + ComplexFloat,
+{
+    if x.is_empty() {
+        return Vec::new();
+    }
+    let size = (2 * x.len() - 1).next_power_of_two();
+    let engine = engine::<T>(size);
+    let mut power = spectrum(&engine, x.iter().copied());
+    for y in power.iter_mut() {
+        let norm = y.abs();
+        *y = T::one() * (norm * norm);
+    }
+    // The circular autocorrelation holds negative lags at the end, rotate them first
+    let circular = inverse(&engine, &power, 0..size);
+    let lags = x.len() - 1;
+    let full = circular[size - lags..]
+        .iter()
+        .chain(circular[..x.len()].iter())
+        .copied()
+        .collect::<Vec<_>>();
+    full[mode.range(x.len(), x.len())].to_vec()
+}
+
+/// Same as [`convolve`] for real inputs, both transformed at once as the real and
+/// imaginary parts of a single complex sequence.
+pub fn convolve_real<R>(a: &[R], b: &[R], mode: Mode) -> Vec<R>
+where
+    R: Float + FloatConst,
+{
+    if a.is_empty() || b.is_empty() {
+        return Vec::new();
+    }
+    convolve_packed(a.iter().copied(), b.iter().copied(), a.len(), b.len(), mode)
+}
+
+/// Same as [`correlate`] for real inputs.
+pub fn correlate_real<R>(a: &[R], b: &[R], mode: Mode) -> Vec<R>
+where
+    R: Float + FloatConst,
+{
+    if a.is_empty() || b.is_empty() {
+        return Vec::new();
+    }
+    convolve_packed(
+        a.iter().copied(),
+        b.iter().rev().copied(),
+        a.len(),
+        b.len(),
+        mode,
+    )
+}
+
+/// Same as [`autocorrelate`] for real inputs.
+pub fn autocorrelate_real<R>(x: &[R], mode: Mode) -> Vec<R>
+where
+    R: Float + FloatConst,
+{
+    let complex = x
+        .iter()
+        .map(|x| Complex::new(*x, R::zero()))
+        .collect::<Vec<_>>();
+    autocorrelate(&complex, mode)
+        .into_iter()
+        .map(|x| x.re)
+        .collect()
+}
+
+/// Convolution of two real sequences packed as `a + i b`: the spectra of `a` and `b` are
+/// the even and odd parts of the packed spectrum, `A[k] = (Z[k] + conj(Z[-k])) / 2` and
+/// `B[k] = (Z[k] - conj(Z[-k])) / 2i`.
+fn convolve_packed<R>(
+    a: impl Iterator<Item = R>,
+    b: impl Iterator<Item = R>,
+    len_a: usize,
+    len_b: usize,
+    mode: Mode,
+) -> Vec<R>
+where
+    R: Float + FloatConst,
+{
+    let size = (len_a + len_b - 1).next_power_of_two();
+    let engine = engine::<Complex<R>>(size);
+    let packed = spectrum(
+        &engine,
+        a.chain(iter::repeat(R::zero()))
+            .zip(b.chain(iter::repeat(R::zero())))
+            .take(size)
+            .map(|(a, b)| Complex::new(a, b)),
+    );
+    let half = scalar::<R>(0.5);
+    let product = (0..size)
+        .map(|k| {
+            let z = packed[k];
+            let mirror = packed[(size - k) % size].conj();
+            let a = (z + mirror) * half;
+            let b = (z - mirror) * Complex::new(R::zero(), -half);
+            a * b
+        })
+        .collect::<Vec<_>>();
+    inverse(&engine, &product, mode.range(len_a, len_b))
+        .into_iter()
+        .map(|x| x.re)
+        .collect()
+}
+
+fn engine<T>(size: usize) -> DynEngine<T, CooleyTukey, Rect>
+where
+    T: Copy
+        + Add<Output = T>
+        + Sub<Output = T>
+        + Mul<Scalar<T>, Output = T>
+        + ImgUnit
+        + ComplexFloat,
+{
+    DynEngine::new(size).expect("padded sizes are powers of two")
+}
+
+/// Transform of `samples` zero-padded to the engine length.
+fn spectrum<T>(engine: &DynEngine<T, CooleyTukey, Rect>, samples: impl Iterator<Item = T>) -> Vec<T>
+where
+    T: Copy
+        + Add<Output = T>
+        + Sub<Output = T>
+        + Mul<Scalar<T>, Output = T>
+        + ImgUnit
+        + ComplexFloat,
+{
+    let padded = samples
+        .chain(iter::repeat(T::zero()))
+        .take(engine.len())
+        .collect::<Vec<_>>();
+    let mut spectrum = vec![T::zero(); engine.len()];
+    engine
+        .fft(&padded, &mut spectrum)
+        .expect("buffers are padded to the engine length");
+    spectrum
+}
+
+/// Normalized inverse transform of `spectrum`, restricted to `range`.
+fn inverse<T>(
+    engine: &DynEngine<T, CooleyTukey, Rect>,
+    spectrum: &[T],
+    range: Range<usize>,
+) -> Vec<T>
+where
+    T: Copy
+        + Add<Output = T>
+        + Sub<Output = T>
+        + Mul<Scalar<T>, Output = T>
+        + ImgUnit
+        + ComplexFloat,
+{
+    let mut output = vec![T::zero(); engine.len()];
+    engine
+        .ifft(spectrum, &mut output, Scaling::Normalized)
+        .expect("buffers are padded to the engine length");
+    output.truncate(range.end);
+    output.drain(..range.start);
+    output
+}
+
+#[cfg(test)]
+mod test {
+    use approx::assert_relative_eq;
+    use num_complex::{Complex32, Complex64};
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    use super::{
+        autocorrelate, autocorrelate_real, convolve, convolve_real, correlate, correlate_real, Mode,
+    };
+
+    /// Full convolution evaluated directly.
+    fn direct(a: &[Complex64], b: &[Complex64]) -> Vec<Complex64> {
+        (0..a.len() + b.len() - 1)
+            .map(|k| {
+                (0..a.len())
+                    .filter(|i| k >= *i && k - i < b.len())
+                    .map(|i| a[i] * b[k - i])
+                    .sum()
+            })
+            .collect()
+    }
+
+    fn signal(seed: u64, len: usize) -> Vec<Complex64> {
+        let mut rng = StdRng::seed_from_u64(seed);
+        (0..len)
+            .map(|_| Complex64::new(rng.gen::<f64>() - 0.5, rng.gen::<f64>() - 0.5))
+            .collect()
+    }
+
+    fn assert_close(actual: &[Complex64], expected: &[Complex64]) {
+        assert_eq!(actual.len(), expected.len());
+        for (a, e) in actual.iter().zip(expected.iter()) {
+            assert_relative_eq!(a.re, e.re, epsilon = 1e-12);
+            assert_relative_eq!(a.im, e.im, epsilon = 1e-12);
+        }
+    }
+
+    #[test]
+    fn modes_test() {
+        let full = convolve_real(&[1.0, 2.0, 3.0], &[0.0, 1.0, 0.5], Mode::Full);
+        for (x, e) in full.iter().zip([0.0, 1.0, 2.5, 4.0, 1.5].iter()) {
+            assert_relative_eq!(*x, *e, epsilon = 1e-12);
+        }
+        let same = convolve_real(&[1.0, 2.0, 3.0], &[0.0, 1.0, 0.5], Mode::Same);
+        for (x, e) in same.iter().zip([1.0, 2.5, 4.0].iter()) {
+            assert_relative_eq!(*x, *e, epsilon = 1e-12);
+        }
+        let valid = convolve_real(&[1.0, 2.0, 3.0], &[0.0, 1.0, 0.5], Mode::Valid);
+        assert_eq!(valid.len(), 1);
+        assert_relative_eq!(valid[0], 2.5, epsilon = 1e-12);
+
+        // Lengths of every mode, whichever input is longer
+        for (a, b) in [(10, 3), (3, 10), (7, 7), (1, 5), (16, 17)] {
+            let x = vec![1.0f32; a];
+            let y = vec![1.0f32; b];
+            assert_eq!(convolve_real(&x, &y, Mode::Full).len(), a + b - 1);
+            assert_eq!(convolve_real(&x, &y, Mode::Same).len(), a);
+            assert_eq!(
+                convolve_real(&x, &y, Mode::Valid).len(),
+                a.max(b) - a.min(b) + 1
+            );
+        }
+
+        assert!(convolve_real::<f64>(&[], &[1.0], Mode::Full).is_empty());
+        assert!(correlate::<Complex32>(&[Complex32::new(1.0, 0.0)], &[], Mode::Same).is_empty());
+        assert!(autocorrelate_real::<f64>(&[], Mode::Full).is_empty());
+    }
+
+    #[test]
+    fn convolve_test() {
+        for (len_a, len_b) in [(1, 1), (5, 3), (3, 5), (64, 64), (100, 17), (33, 250)] {
+            let a = signal(1, len_a);
+            let b = signal(2, len_b);
+            let full = direct(&a, &b);
+            for mode in [Mode::Full, Mode::Same, Mode::Valid] {
+                let range = mode.range(len_a, len_b);
+                assert_close(&convolve(&a, &b, mode), &full[range.clone()]);
+
+                // Real parts alone, through the packed transform
+                let re = |x: &[Complex64]| x.iter().map(|x| x.re).collect::<Vec<_>>();
+                let expected = direct(
+                    &a.iter()
+                        .map(|x| Complex64::new(x.re, 0.0))
+                        .collect::<Vec<_>>(),
+                    &b.iter()
+                        .map(|x| Complex64::new(x.re, 0.0))
+                        .collect::<Vec<_>>(),
+                );
+                for (x, e) in convolve_real(&re(&a), &re(&b), mode)
+                    .iter()
+                    .zip(expected[range].iter())
+                {
+                    assert_relative_eq!(*x, e.re, epsilon = 1e-12);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn correlate_test() {
+        let a = signal(3, 40);
+        let b = signal(4, 25);
+        // Correlation is convolution with the conjugated and reversed second input
+        let reversed = b.iter().rev().map(|x| x.conj()).collect::<Vec<_>>();
+        for mode in [Mode::Full, Mode::Same, Mode::Valid] {
+            assert_close(&correlate(&a, &b, mode), &convolve(&a, &reversed, mode));
+        }
+        assert_close(
+            &autocorrelate(&a, Mode::Full),
+            &correlate(&a, &a, Mode::Full),
+        );
+        assert_close(
+            &autocorrelate(&a, Mode::Same),
+            &correlate(&a, &a, Mode::Same),
+        );
+        let energy = a.iter().map(|x| x.norm_sqr()).sum::<f64>();
+        assert_relative_eq!(
+            autocorrelate(&a, Mode::Full)[39].re,
+            energy,
+            epsilon = 1e-12
+        );
+
+        // Delay estimation: the peak sits at b.len() - 1 + delay
+        let mut rng = StdRng::seed_from_u64(5);
+        let reference = (0..200).map(|_| rng.gen::<f64>() - 0.5).collect::<Vec<_>>();
+        let delayed = [vec![0.0; 13], reference.clone()].concat();
+        let correlation = correlate_real(&delayed, &reference, Mode::Full);
+        let peak = (0..correlation.len())
+            .max_by(|i, j| correlation[*i].total_cmp(&correlation[*j]))
+            .unwrap();
+        assert_eq!(peak, reference.len() - 1 + 13);
+
+        let autocorrelation = autocorrelate_real(&reference, Mode::Full);
+        let expected = correlate_real(&reference, &reference, Mode::Full);
+        for (x, e) in autocorrelation.iter().zip(expected.iter()) {
+            assert_relative_eq!(*x, *e, epsilon = 1e-12);
+        }
+    }
+}
@@ -0,0 +1,243 @@
+use core::{marker::PhantomData, ops::Mul};
+
+use num_complex::ComplexFloat;
+use num_traits::One;
+
+use crate::{scalar, Direction, Error, Scalar, Scaling, WindowFunction};
+
+/// Runtime-length counterpart of [`crate::Implementation`].
+pub trait DynImplementation<T>
+where
+    T: Copy,
+{
+    type Cache;
+
+    /// Builds the cache for sequences of `len` elements, or reports that the
+    /// implementation does not support that length.
+    fn cache(len: usize) -> Result<Self::Cache, Error>;
+
+    /// Transforms the elements yielded by `v`, zero-padded to `output.len()`, into
+    /// `output`.
+    fn transform(
+        v: impl IntoIterator<Item = T>,
+        output: &mut [T],
+        direction: Direction,
+        cache: &Self::Cache,
+    );
+}
+
+/// Counterpart of [`crate::Engine`] for lengths only known at run time, working on slices.
+pub struct DynEngine<T, I, W>
+where
+    I: DynImplementation<T>,
+    W: WindowFunction<T>,
+    T: Copy,
+{
+    impl_marker: PhantomData<I>,
+    window: W,
+    len: usize,
+    cache: <I as DynImplementation<T>>::Cache,
+}
+
+impl<T, I, W> DynEngine<T, I, W>
+where
+    I: DynImplementation<T>,
+    W: WindowFunction<T>,
+    T: Copy,
+{
+    /// Builds the engine and its cache for sequences of `len` elements, with the default
+    /// instance of the window.
+    ///
+    /// Returns [`Error::UnsupportedLength`] if `len` is zero or not supported by `I`.
+    pub fn new(len: usize) -> Result<DynEngine<T, I, W>, Error>
+    where
+        W: Default,
+    {
+        DynEngine::with_window(len, W::default())
+    }
+
+    /// Same as [`DynEngine::new`], for windows carrying parameters or coefficients.
+    pub fn with_window(len: usize, window: W) -> Result<DynEngine<T, I, W>, Error> {
+        if len == 0 {
+            return Err(Error::UnsupportedLength { len });
+        }
+
+        Ok(DynEngine {
+            impl_marker: PhantomData,
+            window,
+            len,
+            cache: <I as DynImplementation<T>>::cache(len)?,
+        })
+    }
+
+    pub fn window(&self) -> &W {
+        &self.window
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn fft(&self, v: &[T], spectrum: &mut [T]) -> Result<(), Error> {
+        self.check_len(v)?;
+        self.check_len(spectrum)?;
+        <I as DynImplementation<T>>::transform(
+            self.window.windowed_dyn(v, self.len),
+            spectrum,
+            Direction::Forward,
+            &self.cache,
+        );
+        Ok(())
+    }
+
+    pub fn ifft(&self, spectrum: &[T], output: &mut [T], scaling: Scaling) -> Result<(), Error>
+    where
+        T: ComplexFloat + Mul<Scalar<T>, Output = T>,
+    {
+        self.check_len(spectrum)?;
+        self.check_len(output)?;
+        <I as DynImplementation<T>>::transform(
+            spectrum.iter().copied(),
+            output,
+            Direction::Inverse,
+            &self.cache,
+        );
+
+        if scaling == Scaling::Normalized {
+            let scale = Scalar::<T>::one() / scalar(self.len);
+            for x in output.iter_mut() {
+                *x = *x * scale;
+            }
+        }
+        Ok(())
+    }
+
+    fn check_len(&self, buffer: &[T]) -> Result<(), Error> {
+        if buffer.len() != self.len {
+            return Err(Error::LengthMismatch {
+                expected: self.len,
+                actual: buffer.len(),
+            });
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use num_complex::Complex32;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    use crate::{
+        allocators::boxed::BoxedAllocator,
+        implementations::{Bluestein, CooleyTukey, MixedRadix, Naive},
+        test::array_assert_eq,
+        windows::{hanning::Hanning, Rect},
+        Allocator, Direction, DynEngine, Engine, Error, Implementation, Scaling, WindowFunction,
+    };
+
+    use super::DynImplementation;
+
+    fn static_ground_truth_test<const N: usize, I, W>()
+    where
+        I: DynImplementation<Complex32> + Implementation<Complex32, N, BoxedAllocator>,
+        W: WindowFunction<Complex32> + Default,
+    {
+        let engine = Engine::<Complex32, N, I, W, BoxedAllocator>::new();
+        let dyn_engine = DynEngine::<Complex32, I, W>::new(N).unwrap();
+
+        let mut rng = StdRng::seed_from_u64(1234);
+        let v = (0..N).map(|_| rng.gen()).collect::<Vec<Complex32>>();
+        let mut fft_v = <BoxedAllocator as Allocator<Complex32, N>>::allocate();
+        engine.fft(v.as_slice(), &mut fft_v);
+        let mut dyn_fft_v = vec![Complex32::default(); N];
+        dyn_engine.fft(&v, &mut dyn_fft_v).unwrap();
+        array_assert_eq(fft_v.as_ref(), dyn_fft_v.as_slice(), 1e-3);
+
+        let mut round_trip = vec![Complex32::default(); N];
+        dyn_engine
+            .ifft(&dyn_fft_v, &mut round_trip, Scaling::Normalized)
+            .unwrap();
+        let windowed = dyn_engine
+            .window()
+            .windowed_dyn(&v, N)
+            .into_iter()
+            .collect::<Vec<_>>();
+        array_assert_eq(windowed.as_slice(), round_trip.as_slice(), 1e-3);
+    }
+
+    #[test]
+    fn ground_truth_test() {
+        static_ground_truth_test::<64, CooleyTukey, Rect>();
+        static_ground_truth_test::<64, CooleyTukey, Hanning>();
+        static_ground_truth_test::<60, Naive, Rect>();
+        static_ground_truth_test::<60, Naive, Hanning>();
+        static_ground_truth_test::<1000, Bluestein, Hanning>();
+        static_ground_truth_test::<1000, MixedRadix, Rect>();
+        static_ground_truth_test::<1000, MixedRadix, Hanning>();
+    }
+
+    #[test]
+    fn invalid_length_test() {
+        assert_eq!(
+            DynEngine::<Complex32, CooleyTukey, Rect>::new(1000).err(),
+            Some(Error::UnsupportedLength { len: 1000 })
+        );
+        assert_eq!(
+            DynEngine::<Complex32, MixedRadix, Rect>::new(0).err(),
+            Some(Error::UnsupportedLength { len: 0 })
+        );
+
+        let engine = DynEngine::<Complex32, MixedRadix, Rect>::new(1000).unwrap();
+        let v = vec![Complex32::default(); 1000];
+        let mut spectrum = vec![Complex32::default(); 999];
+        assert_eq!(
+            engine.fft(&v, &mut spectrum),
+            Err(Error::LengthMismatch {
+                expected: 1000,
+                actual: 999
+            })
+        );
+        assert_eq!(
+            engine.fft(&v[..10], &mut spectrum),
+            Err(Error::LengthMismatch {
+                expected: 1000,
+                actual: 10
+            })
+        );
+    }
+
+    fn short_input_test<I: DynImplementation<Complex32>>(len: usize) {
+        let cache = I::cache(len).unwrap();
+        let mut rng = StdRng::seed_from_u64(1234);
+        let v = (0..len).map(|_| rng.gen()).collect::<Vec<Complex32>>();
+
+        // Stale output from a previous transform must not leak into a shorter input
+        let mut output = vec![Complex32::default(); len];
+        I::transform(v.iter().copied(), &mut output, Direction::Forward, &cache);
+        I::transform(
+            v[..len / 3].iter().copied(),
+            &mut output,
+            Direction::Forward,
+            &cache,
+        );
+
+        let mut padded = vec![Complex32::default(); len];
+        padded[..len / 3].copy_from_slice(&v[..len / 3]);
+        let mut expected = vec![Complex32::default(); len];
+        I::transform(padded, &mut expected, Direction::Forward, &cache);
+        array_assert_eq(expected.as_slice(), output.as_slice(), 1e-4);
+    }
+
+    #[test]
+    fn zero_padding_test() {
+        short_input_test::<CooleyTukey>(64);
+        short_input_test::<Naive>(60);
+        short_input_test::<Bluestein>(100);
+        short_input_test::<MixedRadix>(60);
+    }
+}
@@ -0,0 +1,343 @@
+use core::ops::Mul;
+
+use crate::{
+    windows::Rect, Allocator, ComplexFloat, Engine, Error, Implementation, Scalar, Scaling,
+};
+
+/// Streaming FIR filter by uniformly partitioned overlap-save convolution.
+///
+/// The impulse response is split into P partitions of N/2 taps whose spectra are computed
+/// once. Input is processed in blocks of N/2 samples: each block costs one forward and one
+/// inverse transform of N points plus P spectrum products against a frequency-domain
+/// delay line of the last P input spectra. Long responses thus need more partitions, not
+/// longer blocks, and the latency stays N/2 samples whatever the response length.
+///
+/// All buffers come from the allocator, so with [`crate::allocators::array::ArrayAllocator`]
+/// the filter lives on the stack.
+pub struct FirFilter<T, const N: usize, const P: usize, I, A>
+where
+    A: Allocator<T, N>,
+    I: Implementation<T, N, A>,
+    T: Copy + ComplexFloat + Mul<Scalar<T>, Output = T>,
+{
+    engine: Engine<T, N, I, Rect, A>,
+    /// Spectra of the partitions of the impulse response, zero-padded to N.
+    partitions: [A::Element; P],
+    /// Spectra of the last P input windows, the newest at `head`.
+    history: [A::Element; P],
+    head: usize,
+    /// Last block of input followed by the block being filled.
+    input: A::Element,
+    /// Output of the last complete block in the second half, the first half being scratch.
+    output: A::Element,
+    /// Samples of the current block received so far.
+    position: usize,
+}
+
+impl<T, const N: usize, const P: usize, I, A> FirFilter<T, N, P, I, A>
+where
+    A: Allocator<T, N>,
+    I: Implementation<T, N, A>,
+    T: Copy + ComplexFloat + Mul<Scalar<T>, Output = T>,
+{
+    /// Filter with the taps of `impulse_response`, real or complex.
+    ///
+    /// Returns [`Error::UnsupportedLength`] if N is zero or odd, and [`Error::LengthMismatch`] if
+    /// the response is longer than the P * N/2 taps the partitions hold.
+    pub fn new<S>(impulse_response: &[S]) -> Result<FirFilter<T, N, P, I, A>, Error>
+    where
+        S: Copy + Into<T>,
+    {
+        if N == 0 || N % 2 != 0 {
+            return Err(Error::UnsupportedLength { len: N });
+        }
+        let block = N / 2;
+        if impulse_response.len() > P * block {
+            return Err(Error::LengthMismatch {
+                expected: P * block,
+                actual: impulse_response.len(),
+            });
+        }
+
+        let engine = Engine::new();
+        let mut partitions = core::array::from_fn(|_| A::allocate());
+        for (p, partition) in partitions.iter_mut().enumerate() {
+            let partition: &mut [T] = partition.as_mut();
+            partition.fill(T::zero());
+            let taps = impulse_response.iter().skip(p * block).take(block);
+            for (x, h) in partition.iter_mut().zip(taps) {
+                *x = (*h).into();
+            }
+            engine.fft_in_place(partition);
+        }
+
+        let mut filter = FirFilter {
+            engine,
+            partitions,
+            history: core::array::from_fn(|_| A::allocate()),
+            head: 0,
+            input: A::allocate(),
+            output: A::allocate(),
+            position: 0,
+        };
+        filter.reset();
+        Ok(filter)
+    }
+
+    /// Delay, in samples, of the output on the convolution of the input with the response.
+    pub fn latency(&self) -> usize {
+        N / 2
+    }
+
+    /// Clears the past input, as if the filter had only received zeros.
+    pub fn reset(&mut self) {
+        for buffer in self.history.iter_mut() {
+            buffer.as_mut().fill(T::zero());
+        }
+        self.input.as_mut().fill(T::zero());
+        self.output.as_mut().fill(T::zero());
+        self.head = 0;
+        self.position = 0;
+    }
+
+    /// Filters the next `input.len()` samples into `output`, carrying the state over to
+    /// the next call, so that blocks of any length can be streamed. Output sample `n` is
+    /// sample `n - latency()` of the convolution.
+    ///
+    /// Returns [`Error::LengthMismatch`] if `output` is not as long as `input`.
+    pub fn process(&mut self, input: &[T], output: &mut [T]) -> Result<(), Error> {
+        self.process_with(input, output, |x| x, |y| y)
+    }
+
+    /// Same as [`FirFilter::process`] for real signals, keeping the real part of the
+    /// output.
+    pub fn process_real(
+        &mut self,
+        input: &[Scalar<T>],
+        output: &mut [Scalar<T>],
+    ) -> Result<(), Error> {
+        self.process_with(input, output, |x| T::one() * x, |y| y.re())
+    }
+
+    fn process_with<S: Copy>(
+        &mut self,
+        input: &[S],
+        output: &mut [S],
+        into: impl Fn(S) -> T,
+        from: impl Fn(T) -> S,
+    ) -> Result<(), Error> {
+        if output.len() != input.len() {
+            return Err(Error::LengthMismatch {
+                expected: input.len(),
+                actual: output.len(),
+            });
+        }
+
+        let block = N / 2;
+        let mut done = 0;
+        while done < input.len() {
+            let count = (block - self.position).min(input.len() - done);
+            let current = block + self.position..block + self.position + count;
+            for (x, s) in self.input.as_mut()[current.clone()]
+                .iter_mut()
+                .zip(input[done..done + count].iter())
+            {
+                *x = into(*s);
+            }
+            for (y, s) in output[done..done + count]
+                .iter_mut()
+                .zip(self.output.as_ref()[current].iter())
+            {
+                *y = from(*s);
+            }
+            done += count;
+            self.position += count;
+            if self.position == block {
+                self.block();
+                self.position = 0;
+            }
+        }
+        Ok(())
+    }
+
+    /// Convolves the complete input window with every partition.
+    fn block(&mut self) {
+        let block = N / 2;
+        if P == 0 {
+            // Without partitions the response, hence the output, is empty
+            self.output.as_mut().fill(T::zero());
+            return;
+        }
+        self.head = (self.head + 1) % P;
+        let spectrum = self.history[self.head].as_mut();
+        spectrum.copy_from_slice(self.input.as_ref());
+        self.engine.fft_in_place(spectrum);
+        self.input.as_mut().copy_within(block.., 0);
+
+        // Partition p meets the input spectrum of p blocks ago
+        let accumulator = self.output.as_mut();
+        accumulator.fill(T::zero());
+        for (p, partition) in self.partitions.iter().enumerate() {
+            let spectrum = self.history[(self.head + P - p) % P].as_ref();
+            for ((y, h), x) in accumulator
+                .iter_mut()
+                .zip(partition.as_ref().iter())
+                .zip(spectrum.iter())
+            {
+                *y = *y + *h * *x;
+            }
+        }
+        // The first half wraps around, overlap-save keeps the second
+        self.engine.ifft_in_place(accumulator, Scaling::Normalized);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use approx::assert_relative_eq;
+    use num_complex::{Complex32, Complex64};
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    use crate::{
+        allocators::{array::ArrayAllocator, boxed::BoxedAllocator},
+        implementations::{CooleyTukey, MixedRadix},
+        Error,
+    };
+
+    use super::FirFilter;
+
+    fn signal(seed: u64, len: usize) -> Vec<Complex64> {
+        let mut rng = StdRng::seed_from_u64(seed);
+        (0..len)
+            .map(|_| Complex64::new(rng.gen::<f64>() - 0.5, rng.gen::<f64>() - 0.5))
+            .collect()
+    }
+
+    /// Convolution of `x` with `h`, delayed by `latency` samples, evaluated directly.
+    fn direct(x: &[Complex64], h: &[Complex64], latency: usize) -> Vec<Complex64> {
+        (0..x.len())
+            .map(|n| {
+                (0..h.len())
+                    .filter(|k| n >= latency + k)
+                    .map(|k| h[k] * x[n - latency - k])
+                    .sum()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn streaming_test() {
+        // Responses shorter than, equal to and spanning several partitions
+        for taps in [1, 5, 8, 21, 32] {
+            let h = signal(1, taps);
+            let x = signal(2, 200);
+            let mut filter =
+                FirFilter::<Complex64, 16, 4, CooleyTukey, ArrayAllocator>::new(&h).unwrap();
+            assert_eq!(filter.latency(), 8);
+            let expected = direct(&x, &h, filter.latency());
+
+            // Blocks of irregular lengths, shorter and longer than the partitions
+            let mut output = vec![Complex64::default(); x.len()];
+            let mut start = 0;
+            for len in [1, 3, 7, 8, 0, 19, 2, 64].iter().cycle() {
+                let end = (start + len).min(x.len());
+                filter
+                    .process(&x[start..end], &mut output[start..end])
+                    .unwrap();
+                start = end;
+                if start == x.len() {
+                    break;
+                }
+            }
+            for (y, e) in output.iter().zip(expected.iter()) {
+                assert_relative_eq!(y.re, e.re, epsilon = 1e-12);
+                assert_relative_eq!(y.im, e.im, epsilon = 1e-12);
+            }
+        }
+    }
+
+    #[test]
+    fn real_test() {
+        let mut rng = StdRng::seed_from_u64(3);
+        let h = (0..150).map(|_| rng.gen::<f32>() - 0.5).collect::<Vec<_>>();
+        let x = (0..1000)
+            .map(|_| rng.gen::<f32>() - 0.5)
+            .collect::<Vec<_>>();
+        let mut filter =
+            FirFilter::<Complex32, 64, 5, CooleyTukey, BoxedAllocator>::new(&h).unwrap();
+        let mut output = vec![0.0; x.len()];
+        for (input, output) in x.chunks(100).zip(output.chunks_mut(100)) {
+            filter.process_real(input, output).unwrap();
+        }
+        let widen = |v: &[f32]| {
+            v.iter()
+                .map(|x| Complex64::new(*x as f64, 0.0))
+                .collect::<Vec<_>>()
+        };
+        let expected = direct(&widen(&x), &widen(&h), 32);
+        for (y, e) in output.iter().zip(expected.iter()) {
+            assert_relative_eq!(*y as f64, e.re, epsilon = 1e-4);
+        }
+
+        // Once reset, the filter forgets the past input
+        filter.reset();
+        let mut impulse = [0.0; 200];
+        impulse[0] = 1.0;
+        let mut response = [0.0; 200];
+        filter.process_real(&impulse, &mut response).unwrap();
+        assert!(response[..32].iter().all(|y| y.abs() < 1e-6));
+        for (y, e) in response[32..182].iter().zip(h.iter()) {
+            assert_relative_eq!(*y, *e, epsilon = 1e-5);
+        }
+    }
+
+    #[test]
+    fn mixed_radix_test() {
+        // Blocks of any even size
+        let h = signal(4, 40);
+        let x = signal(5, 120);
+        let mut filter =
+            FirFilter::<Complex64, 30, 3, MixedRadix, BoxedAllocator>::new(&h).unwrap();
+        let mut output = vec![Complex64::default(); x.len()];
+        filter.process(&x, &mut output).unwrap();
+        for (y, e) in output.iter().zip(direct(&x, &h, 15).iter()) {
+            assert_relative_eq!(y.re, e.re, epsilon = 1e-12);
+            assert_relative_eq!(y.im, e.im, epsilon = 1e-12);
+        }
+    }
+
+    #[test]
+    fn invalid_test() {
+        let h = signal(6, 33);
+        assert_eq!(
+            FirFilter::<Complex64, 16, 4, CooleyTukey, ArrayAllocator>::new(&h).err(),
+            Some(Error::LengthMismatch {
+                expected: 32,
+                actual: 33
+            })
+        );
+        assert_eq!(
+            FirFilter::<Complex64, 15, 4, MixedRadix, BoxedAllocator>::new(&h[..4]).err(),
+            Some(Error::UnsupportedLength { len: 15 })
+        );
+
+        let mut filter =
+            FirFilter::<Complex64, 16, 4, CooleyTukey, ArrayAllocator>::new(&h[..4]).unwrap();
+        let mut output = [Complex64::default(); 3];
+        assert_eq!(
+            filter.process(&h[..4], &mut output),
+            Err(Error::LengthMismatch {
+                expected: 4,
+                actual: 3
+            })
+        );
+
+        // Without partitions, only an empty response fits and the output is silent
+        let mut silent =
+            FirFilter::<Complex64, 16, 0, CooleyTukey, ArrayAllocator>::new::<Complex64>(&[])
+                .unwrap();
+        let mut output = [Complex64::new(1.0, 1.0); 40];
+        silent.process(&signal(7, 40), &mut output).unwrap();
+        assert!(output.iter().all(|y| *y == Complex64::default()));
+    }
+}
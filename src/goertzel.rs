@@ -0,0 +1,344 @@
+use core::ops::Mul;
+
+use num_traits::{Float, FloatConst, Zero};
+
+use crate::{scalar, Allocator, ComplexFloat, Error, ImgUnit, Scalar};
+
+/// Single-bin DFT of frames of `len` samples by the Goertzel recursion: one real
+/// multiplication per sample, then a single complex step at the end of the frame.
+///
+/// Cheaper than a full transform when only a few bins are needed, and not limited to
+/// integer bins (see [`Goertzel::generalized`]).
+#[derive(Clone, Copy)]
+pub struct Goertzel<T: ComplexFloat> {
+    len: usize,
+    /// `2 cos ω`, the coefficient of the recursion.
+    coefficient: Scalar<T>,
+    /// `e^{-jω}`, combining the last two states.
+    twiddle: T,
+    /// `e^{-jω (len - 1)}`, aligning the phase on the first sample.
+    phase: T,
+}
+
+impl<T> Goertzel<T>
+where
+    T: Copy + ComplexFloat + Mul<Scalar<T>, Output = T> + ImgUnit,
+    Scalar<T>: FloatConst,
+{
+    /// Detector of bin `bin` of frames of `len` samples, equal to bin `bin` of their DFT.
+    pub fn new(bin: usize, len: usize) -> Goertzel<T> {
+        Goertzel::generalized(scalar(bin), len)
+    }
+
+    /// Detector of the possibly fractional bin `bin` of frames of `len` samples: the DTFT
+    /// `Σ x[n] e^{-jωn}` at `ω = 2π bin / len`.
+    pub fn generalized(bin: Scalar<T>, len: usize) -> Goertzel<T> {
+        let omega = Scalar::<T>::TAU() * bin / scalar(len);
+        let last = scalar::<Scalar<T>>(len.saturating_sub(1));
+        Goertzel {
+            len,
+            coefficient: scalar::<Scalar<T>>(2) * Float::cos(omega),
+            twiddle: polar::<T>(-omega),
+            phase: polar::<T>(-omega * last),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Value of the bin over the `len` samples of `samples`.
+    ///
+    /// Returns [`Error::LengthMismatch`] if `samples` does not hold `len` samples.
+    pub fn bin(&self, samples: &[T]) -> Result<T, Error> {
+        self.check_len(samples.len())?;
+        let (s1, s2) = samples.iter().fold((T::zero(), T::zero()), |(s1, s2), x| {
+            (*x + s1 * self.coefficient - s2, s1)
+        });
+        Ok((s1 - self.twiddle * s2) * self.phase)
+    }
+
+    /// Same as [`Goertzel::bin`] for real samples, running the recursion in real
+    /// arithmetic.
+    pub fn bin_real(&self, samples: &[Scalar<T>]) -> Result<T, Error> {
+        self.check_len(samples.len())?;
+        let (s1, s2) = samples
+            .iter()
+            .fold((Scalar::<T>::zero(), Scalar::<T>::zero()), |(s1, s2), x| {
+                (*x + s1 * self.coefficient - s2, s1)
+            });
+        Ok((T::one() * s1 - self.twiddle * s2) * self.phase)
+    }
+
+    /// Squared magnitude of the bin over the real `samples`, which needs no complex
+    /// arithmetic at all.
+    ///
+    /// Returns [`Error::LengthMismatch`] if `samples` does not hold `len` samples.
+    pub fn power_real(&self, samples: &[Scalar<T>]) -> Result<Scalar<T>, Error> {
+        self.check_len(samples.len())?;
+        let (s1, s2) = samples
+            .iter()
+            .fold((Scalar::<T>::zero(), Scalar::<T>::zero()), |(s1, s2), x| {
+                (*x + s1 * self.coefficient - s2, s1)
+            });
+        Ok(s1 * s1 + s2 * s2 - self.coefficient * s1 * s2)
+    }
+
+    fn check_len(&self, len: usize) -> Result<(), Error> {
+        if len != self.len {
+            return Err(Error::LengthMismatch {
+                expected: self.len,
+                actual: len,
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Recursive sliding DFT: K chosen bins of the DFT of the last N samples, updated with a
+/// single complex multiplication per bin as each sample comes in.
+///
+/// The recursion would slowly accumulate rounding errors, so the bins are recomputed
+/// exactly every N samples, which costs as much as the updates in the meantime.
+pub struct SlidingDft<T, const N: usize, const K: usize, A>
+where
+    A: Allocator<T, N>,
+    T: Copy + ComplexFloat,
+{
+    bins: [usize; K],
+    /// `e^{j2πk/N}` for each bin.
+    twiddles: [T; K],
+    values: [T; K],
+    /// Last N samples, the oldest at `position`.
+    history: A::Element,
+    position: usize,
+}
+
+impl<T, const N: usize, const K: usize, A> SlidingDft<T, N, K, A>
+where
+    A: Allocator<T, N>,
+    T: Copy + ComplexFloat + Mul<Scalar<T>, Output = T> + ImgUnit,
+    Scalar<T>: FloatConst,
+{
+    /// Sliding DFT of bins `bins` over the last N samples, starting from N zeros.
+    ///
+    /// # Panics
+    /// Panics if a bin is not smaller than N.
+    pub fn new(bins: [usize; K]) -> SlidingDft<T, N, K, A> {
+        assert!(
+            bins.iter().all(|k| *k < N),
+            "bins must be smaller than N = {N}"
+        );
+        let mut sliding = SlidingDft {
+            bins,
+            twiddles: bins.map(|k| polar::<T>(Scalar::<T>::TAU() * scalar(k) / scalar(N))),
+            values: [T::zero(); K],
+            history: A::allocate(),
+            position: 0,
+        };
+        sliding.reset();
+        sliding
+    }
+
+    pub fn bins(&self) -> &[usize; K] {
+        &self.bins
+    }
+
+    /// Current value of each bin: `Σ x[m] e^{-j2πkm/N}` over the last N samples, `x[0]`
+    /// being the oldest.
+    pub fn values(&self) -> &[T; K] {
+        &self.values
+    }
+
+    /// Forgets the past samples, as if the last N were zeros.
+    pub fn reset(&mut self) {
+        self.history.as_mut().fill(T::zero());
+        self.values = [T::zero(); K];
+        self.position = 0;
+    }
+
+    /// Slides the window by one sample, real or complex, and returns the updated bins.
+    pub fn push<S: Into<T>>(&mut self, sample: S) -> &[T; K] {
+        let sample = sample.into();
+        let history = self.history.as_mut();
+        let oldest = history[self.position];
+        history[self.position] = sample;
+        self.position = (self.position + 1) % N;
+
+        if self.position == 0 {
+            // The history is in chronological order again, recompute from scratch
+            for (value, k) in self.values.iter_mut().zip(self.bins.iter()) {
+                let goertzel = Goertzel::<T>::new(*k, N);
+                *value = goertzel
+                    .bin(self.history.as_ref())
+                    .expect("the history holds N samples");
+            }
+        } else {
+            for (value, twiddle) in self.values.iter_mut().zip(self.twiddles.iter()) {
+                *value = (*value + sample - oldest) * *twiddle;
+            }
+        }
+        &self.values
+    }
+
+    /// Pushes every sample of `samples`, then returns the bins.
+    pub fn extend<S: Copy + Into<T>>(&mut self, samples: &[S]) -> &[T; K] {
+        for sample in samples.iter() {
+            self.push(*sample);
+        }
+        &self.values
+    }
+}
+
+/// `e^{jθ}` as an element.
+fn polar<T>(theta: Scalar<T>) -> T
+where
+    T: Copy + ComplexFloat + Mul<Scalar<T>, Output = T> + ImgUnit,
+{
+    T::one() * Float::cos(theta) + T::img_unit() * Float::sin(theta)
+}
+
+#[cfg(test)]
+mod test {
+    use approx::assert_relative_eq;
+    use num_complex::{Complex32, Complex64};
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    use crate::{
+        allocators::array::ArrayAllocator, implementations::Naive, windows::Rect, Allocator,
+        Engine, Error,
+    };
+
+    use super::{Goertzel, SlidingDft};
+
+    const N: usize = 24;
+
+    fn signal(seed: u64, len: usize) -> Vec<Complex64> {
+        let mut rng = StdRng::seed_from_u64(seed);
+        (0..len)
+            .map(|_| Complex64::new(rng.gen::<f64>() - 0.5, rng.gen::<f64>() - 0.5))
+            .collect()
+    }
+
+    fn naive(samples: &[Complex64]) -> [Complex64; N] {
+        let engine = Engine::<Complex64, N, Naive, Rect, ArrayAllocator>::new();
+        let mut spectrum = <ArrayAllocator as Allocator<Complex64, N>>::allocate();
+        engine.fft(samples, &mut spectrum);
+        spectrum
+    }
+
+    fn assert_close(actual: Complex64, expected: Complex64, epsilon: f64) {
+        assert_relative_eq!(actual.re, expected.re, epsilon = epsilon);
+        assert_relative_eq!(actual.im, expected.im, epsilon = epsilon);
+    }
+
+    #[test]
+    fn goertzel_test() {
+        let x = signal(1, N);
+        let real = x.iter().map(|x| x.re).collect::<Vec<_>>();
+        let spectrum = naive(&x);
+        let real_spectrum = naive(
+            &real
+                .iter()
+                .map(|x| Complex64::new(*x, 0.0))
+                .collect::<Vec<_>>(),
+        );
+        for k in 0..N {
+            let goertzel = Goertzel::<Complex64>::new(k, N);
+            assert_close(goertzel.bin(&x).unwrap(), spectrum[k], 1e-12);
+            assert_close(goertzel.bin_real(&real).unwrap(), real_spectrum[k], 1e-12);
+            assert_relative_eq!(
+                goertzel.power_real(&real).unwrap(),
+                real_spectrum[k].norm_sqr(),
+                epsilon = 1e-12
+            );
+
+            // Integer bins of the generalized detector are the same bins
+            let generalized = Goertzel::<Complex64>::generalized(k as f64, N);
+            assert_close(generalized.bin(&x).unwrap(), spectrum[k], 1e-12);
+        }
+
+        assert_eq!(
+            Goertzel::<Complex64>::new(3, N).bin(&x[1..]),
+            Err(Error::LengthMismatch {
+                expected: N,
+                actual: N - 1
+            })
+        );
+    }
+
+    #[test]
+    fn generalized_test() {
+        let x = signal(2, N);
+        for bin in [0.25, 2.5, 7.1, 23.9] {
+            let omega = 2.0 * core::f64::consts::PI * bin / N as f64;
+            let expected = x
+                .iter()
+                .enumerate()
+                .map(|(n, x)| x * Complex64::from_polar(1.0, -omega * n as f64))
+                .sum::<Complex64>();
+            let goertzel = Goertzel::<Complex64>::generalized(bin, N);
+            assert_close(goertzel.bin(&x).unwrap(), expected, 1e-12);
+        }
+
+        // A tone between two bins is caught at its exact frequency
+        let tone = (0..N)
+            .map(|n| (2.0 * core::f64::consts::PI * 5.5 * n as f64 / N as f64).cos())
+            .collect::<Vec<_>>();
+        let at = |bin| {
+            Goertzel::<Complex64>::generalized(bin, N)
+                .bin_real(&tone)
+                .unwrap()
+                .norm()
+        };
+        assert!(at(5.5) > at(5.0) && at(5.5) > at(6.0));
+        assert_relative_eq!(at(5.5), N as f64 / 2.0, epsilon = 0.1);
+    }
+
+    #[test]
+    fn sliding_test() {
+        let x = signal(3, 10 * N + 7);
+        let mut sliding = SlidingDft::<Complex64, N, 3, ArrayAllocator>::new([0, 5, 23]);
+        let mut window = vec![Complex64::default(); N];
+        for sample in x.iter() {
+            let values = *sliding.push(*sample);
+            window.remove(0);
+            window.push(*sample);
+            let spectrum = naive(&window);
+            for (value, k) in values.iter().zip(sliding.bins().iter()) {
+                assert_close(*value, spectrum[*k], 1e-12);
+            }
+        }
+
+        sliding.reset();
+        let values = *sliding.extend(&x[..N]);
+        let spectrum = naive(&x[..N]);
+        assert_close(values[1], spectrum[5], 1e-12);
+    }
+
+    #[test]
+    fn drift_test() {
+        // In single precision, errors stay bounded over long runs
+        let mut rng = StdRng::seed_from_u64(4);
+        let x = (0..100_000)
+            .map(|_| rng.gen::<f32>() - 0.5)
+            .collect::<Vec<_>>();
+        let mut sliding = SlidingDft::<Complex32, N, 1, ArrayAllocator>::new([7]);
+        sliding.extend(&x[..x.len() - 5]);
+        let value = sliding.extend(&x[x.len() - 5..])[0];
+        let expected = Goertzel::<Complex64>::new(7, N)
+            .bin_real(
+                &x[x.len() - N..]
+                    .iter()
+                    .map(|x| *x as f64)
+                    .collect::<Vec<_>>(),
+            )
+            .unwrap();
+        assert_relative_eq!(value.re as f64, expected.re, epsilon = 1e-4);
+        assert_relative_eq!(value.im as f64, expected.im, epsilon = 1e-4);
+    }
+}
@@ -3,3 +3,20 @@ pub mod cooley_tukey;
 
 pub struct Naive;
 pub mod naive;
+
+/// Chirp-z transform for arbitrary N, evaluated as a convolution through a
+/// power-of-two [`CooleyTukey`] transform.
+///
+/// Requires the `alloc` feature, since the padded convolution length is not known
+/// at the type level.
+#[cfg(feature = "alloc")]
+pub struct Bluestein;
+#[cfg(feature = "alloc")]
+pub mod bluestein;
+
+/// Mixed-radix Cooley-Tukey for any N, with dedicated radix-2, 3, 4, 5 and 7 butterflies.
+/// Other prime factors up to 64 go through a generic radix-p butterfly in O(N·p). With
+/// `alloc`, larger ones are transformed by Bluestein's algorithm, keeping prime lengths in
+/// O(N log N); without it they stay in O(N·p), down to [`Naive`] cost for a prime N.
+pub struct MixedRadix;
+pub mod mixed_radix;
@@ -0,0 +1,227 @@
+use alloc::boxed::Box;
+
+use core::ops::{Add, Mul, Sub};
+
+use num_traits::One;
+
+use crate::{
+    dynamic::DynImplementation, scalar, Allocator, ComplexFloat, Direction, Error, ImgUnit,
+    Implementation, Scalar,
+};
+
+use super::{
+    cooley_tukey::{calculate_twiddle, radix2_in_place, twiddles},
+    Bluestein,
+};
+
+/// Chirp and convolution kernel of a Bluestein transform of runtime length
+pub struct Chirp<T> {
+    /// Chirp `exp(-i * pi * n^2 / N)` for `n` in `0..N`
+    chirp: Box<[T]>,
+    /// Spectrum of the conjugated chirp, wrapped around a power-of-two length `M >= 2N - 1`
+    kernel: Box<[T]>,
+    /// Forward twiddles of the `M` point transform
+    twiddles: Box<[T]>,
+}
+
+impl<T> Chirp<T>
+where
+    T: Copy
+        + Add<Output = T>
+        + Sub<Output = T>
+        + Mul<Scalar<T>, Output = T>
+        + ImgUnit
+        + ComplexFloat,
+{
+    pub fn new(len: usize) -> Self {
+        let m = (2 * len).saturating_sub(1).next_power_of_two();
+
+        // exp(-i * pi * n^2 / N) = W_2N^(n^2), with n^2 reduced modulo 2N to keep the
+        // chirp phase accurate for large n
+        let chirp = (0..len)
+            .map(|n| calculate_twiddle::<T>((n * n) % (2 * len), 2 * len))
+            .collect::<Box<[T]>>();
+        let twiddles = twiddles::<T>(m);
+
+        let mut kernel = vec![T::zero(); m].into_boxed_slice();
+        for (n, c) in chirp.iter().enumerate() {
+            kernel[n] = c.conj();
+            kernel[(m - n) % m] = c.conj();
+        }
+        radix2_in_place(&mut kernel, |i| twiddles[i], Direction::Forward);
+
+        Self {
+            chirp,
+            kernel,
+            twiddles,
+        }
+    }
+
+    pub(crate) fn transform(
+        &self,
+        v: impl IntoIterator<Item = T>,
+        spectrum: &mut [T],
+        direction: Direction,
+    ) {
+        // The inverse transform is the conjugate of the forward transform of the conjugate
+        let conj = |x: T| match direction {
+            Direction::Forward => x,
+            Direction::Inverse => x.conj(),
+        };
+
+        let m = self.kernel.len();
+        let mut buffer = vec![T::zero(); m];
+        for ((i, x), c) in v.into_iter().enumerate().zip(self.chirp.iter()) {
+            buffer[i] = conj(x) * *c;
+        }
+
+        radix2_in_place(&mut buffer, |i| self.twiddles[i], Direction::Forward);
+        for (x, k) in buffer.iter_mut().zip(self.kernel.iter()) {
+            *x = *x * *k;
+        }
+        radix2_in_place(&mut buffer, |i| self.twiddles[i], Direction::Inverse);
+
+        let scale = Scalar::<T>::one() / scalar(m);
+        for ((x, y), c) in spectrum
+            .iter_mut()
+            .zip(buffer.iter())
+            .zip(self.chirp.iter())
+        {
+            *x = conj(*y * *c * scale);
+        }
+    }
+}
+
+pub struct ChirpCache<T, const N: usize> {
+    chirp: Chirp<T>,
+}
+
+impl<T, const N: usize> Default for ChirpCache<T, N>
+where
+    T: Copy
+        + Add<Output = T>
+        + Sub<Output = T>
+        + Mul<Scalar<T>, Output = T>
+        + ImgUnit
+        + ComplexFloat,
+{
+    fn default() -> Self {
+        Self {
+            chirp: Chirp::new(N),
+        }
+    }
+}
+
+impl<T, const N: usize, A> Implementation<T, N, A> for Bluestein
+where
+    A: Allocator<T, N>,
+    T: Copy
+        + Add<Output = T>
+        + Sub<Output = T>
+        + Mul<Scalar<T>, Output = T>
+        + ImgUnit
+        + ComplexFloat,
+{
+    type Cache = ChirpCache<T, N>;
+
+    fn transform(
+        v: impl IntoIterator<Item = T>,
+        spectrum: &mut A::Element,
+        direction: Direction,
+        cache: &Self::Cache,
+    ) {
+        cache.chirp.transform(v, spectrum.as_mut(), direction);
+    }
+
+    /// The padded convolution needs more than N elements of workspace, so this
+    /// still allocates.
+    fn transform_with_scratch(
+        buffer: &mut [T],
+        scratch: &mut [T],
+        direction: Direction,
+        cache: &Self::Cache,
+    ) {
+        scratch.copy_from_slice(buffer);
+        cache
+            .chirp
+            .transform(scratch.iter().copied(), buffer, direction);
+    }
+}
+
+impl<T> DynImplementation<T> for Bluestein
+where
+    T: Copy
+        + Add<Output = T>
+        + Sub<Output = T>
+        + Mul<Scalar<T>, Output = T>
+        + ImgUnit
+        + ComplexFloat,
+{
+    type Cache = Chirp<T>;
+
+    fn cache(len: usize) -> Result<Self::Cache, Error> {
+        Ok(Chirp::new(len))
+    }
+
+    fn transform(
+        v: impl IntoIterator<Item = T>,
+        output: &mut [T],
+        direction: Direction,
+        cache: &Self::Cache,
+    ) {
+        cache.transform(v, output, direction);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        implementations::Bluestein,
+        test::{ComplexTestFixture, DoubleComplexTestFixture, SizedComplexTestFixture},
+    };
+
+    #[test]
+    fn impulse_test() {
+        ComplexTestFixture::<Bluestein>::impulse_test();
+        SizedComplexTestFixture::<Bluestein, 1000>::impulse_test();
+    }
+
+    #[test]
+    fn linearity_test() {
+        ComplexTestFixture::<Bluestein>::linearity_test();
+        SizedComplexTestFixture::<Bluestein, 15>::linearity_test();
+    }
+
+    #[test]
+    fn ground_truth_test() {
+        ComplexTestFixture::<Bluestein>::ground_truth_test();
+        SizedComplexTestFixture::<Bluestein, 1>::ground_truth_test();
+        SizedComplexTestFixture::<Bluestein, 12>::ground_truth_test();
+        SizedComplexTestFixture::<Bluestein, 97>::ground_truth_test();
+        SizedComplexTestFixture::<Bluestein, 1000>::ground_truth_test();
+        SizedComplexTestFixture::<Bluestein, 1500>::ground_truth_test();
+    }
+
+    #[test]
+    fn round_trip_test() {
+        ComplexTestFixture::<Bluestein>::round_trip_test();
+        SizedComplexTestFixture::<Bluestein, 100>::round_trip_test();
+    }
+
+    #[test]
+    fn in_place_test() {
+        ComplexTestFixture::<Bluestein>::in_place_test();
+    }
+
+    #[test]
+    fn double_precision_test() {
+        DoubleComplexTestFixture::<Bluestein>::ground_truth_test();
+        DoubleComplexTestFixture::<Bluestein>::round_trip_test();
+    }
+
+    #[test]
+    fn inverse_ground_truth_test() {
+        ComplexTestFixture::<Bluestein>::inverse_ground_truth_test();
+        SizedComplexTestFixture::<Bluestein, 100>::inverse_ground_truth_test();
+    }
+}
@@ -1,12 +1,13 @@
-use crate::{Allocator, ImgUnit, Implementation, Scalar, PI};
+#[cfg(feature = "alloc")]
+use crate::{dynamic::DynImplementation, Error};
+use crate::{scalar, Allocator, Direction, ImgUnit, Implementation, Scalar};
 
 #[cfg(feature = "alloc")]
 use alloc::boxed::Box;
 
 use core::ops::{Add, Mul, Sub};
 
-#[allow(unused_imports)]
-use micromath::F32Ext;
+use num_traits::FloatConst;
 
 use crate::ComplexFloat;
 
@@ -20,7 +21,7 @@ pub struct TwiddleCache<T, const N: usize> {
 #[cfg(feature = "alloc")]
 impl<T, const N: usize> TwiddleCache<T, N>
 where
-    T: Copy + ImgUnit + ComplexFloat + Mul<Scalar, Output = T>,
+    T: Copy + ImgUnit + ComplexFloat + Mul<Scalar<T>, Output = T>,
 {
     pub fn get(&self, i: usize) -> T {
         self.cache[i]
@@ -30,19 +31,24 @@ where
 #[cfg(feature = "alloc")]
 impl<T, const N: usize> Default for TwiddleCache<T, N>
 where
-    T: Copy + ImgUnit + ComplexFloat + Mul<Scalar, Output = T>,
+    T: Copy + ImgUnit + ComplexFloat + Mul<Scalar<T>, Output = T>,
 {
     fn default() -> Self {
-        let half_n = N >> 1;
-        let f_n = N as Scalar;
-        let mut twiddles = vec![T::zero(); half_n].into_boxed_slice();
-        for (i, twiddle) in twiddles.iter_mut().enumerate() {
-            *twiddle = calculate_twiddle::<T>(i, f_n);
-        }
-        Self { cache: twiddles }
+        Self { cache: twiddles(N) }
     }
 }
 
+/// Forward twiddles `W_len^i` for `i` in `0..len / 2`.
+#[cfg(feature = "alloc")]
+pub(crate) fn twiddles<T>(len: usize) -> Box<[T]>
+where
+    T: Copy + ImgUnit + ComplexFloat + Mul<Scalar<T>, Output = T>,
+{
+    (0..len >> 1)
+        .map(|i| calculate_twiddle::<T>(i, len))
+        .collect()
+}
+
 #[inline]
 fn bit_reversal(k: usize, log_n: usize) -> usize {
     let mut k = k;
@@ -55,18 +61,105 @@ fn bit_reversal(k: usize, log_n: usize) -> usize {
     r
 }
 
-fn calculate_twiddle<T>(i: usize, f_n: Scalar) -> T
+/// Forward twiddle `W_n^i = exp(-2πj·i/n)`.
+pub(crate) fn calculate_twiddle<T>(i: usize, n: usize) -> T
 where
-    T: Copy + ImgUnit + ComplexFloat + Mul<Scalar, Output = T>,
+    T: Copy + ImgUnit + ComplexFloat + Mul<Scalar<T>, Output = T>,
 {
-    let omega = -2.0 * PI * (i as Scalar) / f_n;
+    let omega = -scalar::<Scalar<T>>(2) * Scalar::<T>::PI() * scalar(i) / scalar(n);
     (T::img_unit() * omega).exp()
 }
 
+/// Forward twiddle `W_(N / stride)^i`, read from the cache when one is available.
+#[inline]
+pub(crate) fn twiddle<T, const N: usize, A>(
+    _cache: &<CooleyTukey as Implementation<T, N, A>>::Cache,
+    i: usize,
+    stride: usize,
+) -> T
+where
+    A: Allocator<T, N>,
+    T: Copy
+        + Add<Output = T>
+        + Sub<Output = T>
+        + Mul<Scalar<T>, Output = T>
+        + ImgUnit
+        + ComplexFloat,
+{
+    #[cfg(feature = "alloc")]
+    {
+        _cache.get(i * stride)
+    }
+
+    #[cfg(not(feature = "alloc"))]
+    {
+        calculate_twiddle::<T>(i * stride, N)
+    }
+}
+
+/// In-place radix-2 transform of `buffer`, whose length must be a power of two.
+/// `twiddle(i)` must return the forward twiddle `W_n^i` for `n = buffer.len()`.
+pub(crate) fn radix2_in_place<T>(
+    buffer: &mut [T],
+    twiddle: impl Fn(usize) -> T,
+    direction: Direction,
+) where
+    T: Copy + Add<Output = T> + Sub<Output = T> + ComplexFloat,
+{
+    let n = buffer.len();
+    let log_n = n.trailing_zeros() as usize;
+    for i in 0..n {
+        let j = bit_reversal(i, log_n);
+        if i < j {
+            buffer.swap(i, j);
+        }
+    }
+
+    butterflies(buffer, 1, twiddle, direction);
+}
+
+fn butterflies<T>(
+    buffer: &mut [T],
+    mut stride: usize,
+    twiddle: impl Fn(usize) -> T,
+    direction: Direction,
+) where
+    T: Copy + Add<Output = T> + Sub<Output = T> + ComplexFloat,
+{
+    let n = buffer.len();
+    while stride < n {
+        let sublen = n / (stride * 2);
+        for j in (0..n).step_by(stride * 2) {
+            let mut m = 0;
+
+            for k in j..j + stride {
+                // Inverse twiddles are the conjugates of the forward ones
+                let twiddle = match direction {
+                    Direction::Forward => twiddle(m),
+                    Direction::Inverse => twiddle(m).conj(),
+                };
+
+                let a = buffer[k + stride] * twiddle;
+                let b = buffer[k];
+                buffer[k] = b + a;
+                buffer[k + stride] = b - a;
+
+                m += sublen;
+            }
+        }
+        stride <<= 1;
+    }
+}
+
 impl<T, const N: usize, A> Implementation<T, N, A> for CooleyTukey
 where
     A: Allocator<T, N>,
-    T: Copy + Add<Output = T> + Sub<Output = T> + Mul<Scalar, Output = T> + ImgUnit + ComplexFloat,
+    T: Copy
+        + Add<Output = T>
+        + Sub<Output = T>
+        + Mul<Scalar<T>, Output = T>
+        + ImgUnit
+        + ComplexFloat,
 {
     #[cfg(feature = "alloc")]
     type Cache = TwiddleCache<T, N>;
@@ -74,67 +167,88 @@ where
     #[cfg(not(feature = "alloc"))]
     type Cache = ();
 
-    fn fft(v: impl IntoIterator<Item = T>, spectrum: &mut A::Element, cache: &Self::Cache) {
-        // Since we could be in a circular buffer, we copy data to a local buffer.
-        // TODO: Maybe avoid this (without complicating the interface too much)
-        let mut buffer = A::allocate();
-        let buffer = buffer.as_mut();
-        let spectrum = spectrum.as_mut();
+    const ASSERT_SUPPORTED: () = assert!(
+        N.is_power_of_two(),
+        "CooleyTukey requires N to be a power of two, use MixedRadix or Bluestein otherwise"
+    );
 
-        for (i, x) in v.into_iter().enumerate() {
-            buffer[i] = x;
+    fn transform(
+        v: impl IntoIterator<Item = T>,
+        spectrum: &mut A::Element,
+        direction: Direction,
+        cache: &Self::Cache,
+    ) {
+        // The spectrum doubles as the working buffer, so no scratch is needed
+        let spectrum = spectrum.as_mut();
+        let mut len = 0;
+        for x in v {
+            spectrum[len] = x;
+            len += 1;
         }
+        spectrum[len..].fill(T::zero());
 
-        // 1. Bit-reversal permutation
-        let log_n = N.trailing_zeros() as usize;
-        let half_n = N >> 1;
+        <Self as Implementation<T, N, A>>::transform_in_place(spectrum, direction, cache);
+    }
 
-        #[cfg(not(feature = "alloc"))]
-        let f_n = N as Scalar;
+    fn transform_with_scratch(
+        buffer: &mut [T],
+        _scratch: &mut [T],
+        direction: Direction,
+        cache: &Self::Cache,
+    ) {
+        <Self as Implementation<T, N, A>>::transform_in_place(buffer, direction, cache);
+    }
 
-        for i in (0..N).step_by(2) {
-            let j = bit_reversal(i, log_n);
-            let k = bit_reversal(i + 1, log_n);
+    fn transform_in_place(buffer: &mut [T], direction: Direction, cache: &Self::Cache) {
+        #[allow(clippy::let_unit_value)]
+        let () = <Self as Implementation<T, N, A>>::ASSERT_SUPPORTED;
+
+        radix2_in_place(buffer, |i| twiddle::<T, N, A>(cache, i, 1), direction);
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T> DynImplementation<T> for CooleyTukey
+where
+    T: Copy
+        + Add<Output = T>
+        + Sub<Output = T>
+        + Mul<Scalar<T>, Output = T>
+        + ImgUnit
+        + ComplexFloat,
+{
+    type Cache = Box<[T]>;
 
-            spectrum[i] = buffer[j] + buffer[k];
-            spectrum[i + 1] = buffer[j] - buffer[k];
+    fn cache(len: usize) -> Result<Self::Cache, Error> {
+        if !len.is_power_of_two() {
+            return Err(Error::UnsupportedLength { len });
         }
+        Ok(twiddles(len))
+    }
 
-        // 2. Butterfly computation
-        let mut sublen = half_n;
-        let mut stride = 2;
-        for _ in 1..log_n {
-            sublen >>= 1;
-            for j in (0..N).step_by(stride * 2) {
-                #[cfg(feature = "alloc")]
-                let mut m = 0;
-
-                for k in j..j + stride {
-                    #[cfg(feature = "alloc")]
-                    let twiddle = cache.get(m);
-
-                    #[cfg(not(feature = "alloc"))]
-                    let twiddle = calculate_twiddle::<T>(k, f_n);
-
-                    let a = spectrum[k + stride] * twiddle;
-                    let b = spectrum[k];
-                    spectrum[k] = b + a;
-                    spectrum[k + stride] = b - a;
-
-                    #[cfg(feature = "alloc")]
-                    {
-                        m += sublen;
-                    }
-                }
-            }
-            stride <<= 1;
+    fn transform(
+        v: impl IntoIterator<Item = T>,
+        output: &mut [T],
+        direction: Direction,
+        cache: &Self::Cache,
+    ) {
+        // The output doubles as the working buffer, so no scratch is needed
+        let mut len = 0;
+        for x in v {
+            output[len] = x;
+            len += 1;
         }
+        output[len..].fill(T::zero());
+        radix2_in_place(output, |i| cache[i], direction);
     }
 }
 
 #[cfg(test)]
 mod test {
-    use crate::{implementations::CooleyTukey, test::ComplexTestFixture};
+    use crate::{
+        implementations::CooleyTukey,
+        test::{ComplexTestFixture, DoubleComplexTestFixture},
+    };
 
     #[test]
     fn impulse_test() {
@@ -150,4 +264,25 @@ mod test {
     fn ground_truth_test() {
         ComplexTestFixture::<CooleyTukey>::ground_truth_test();
     }
+
+    #[test]
+    fn round_trip_test() {
+        ComplexTestFixture::<CooleyTukey>::round_trip_test();
+    }
+
+    #[test]
+    fn in_place_test() {
+        ComplexTestFixture::<CooleyTukey>::in_place_test();
+    }
+
+    #[test]
+    fn double_precision_test() {
+        DoubleComplexTestFixture::<CooleyTukey>::ground_truth_test();
+        DoubleComplexTestFixture::<CooleyTukey>::round_trip_test();
+    }
+
+    #[test]
+    fn inverse_ground_truth_test() {
+        ComplexTestFixture::<CooleyTukey>::inverse_ground_truth_test();
+    }
 }
@@ -0,0 +1,448 @@
+use core::{
+    marker::PhantomData,
+    ops::{Add, Mul, Sub},
+};
+
+#[cfg(feature = "alloc")]
+use alloc::{boxed::Box, vec::Vec};
+
+#[cfg(feature = "alloc")]
+use crate::{dynamic::DynImplementation, Error};
+use crate::{Allocator, ComplexFloat, Direction, ImgUnit, Implementation, Scalar};
+
+#[cfg(feature = "alloc")]
+use super::bluestein::Chirp;
+use super::{cooley_tukey::calculate_twiddle, MixedRadix};
+
+const MAX_STAGES: usize = usize::BITS as usize;
+
+/// Largest prime factor transformed by a direct butterfly. Under `alloc`, larger ones go
+/// through Bluestein's algorithm, in O(p log p) rather than O(p²) per butterfly.
+#[cfg(feature = "alloc")]
+const MAX_DIRECT_RADIX: usize = 64;
+
+/// Factorization and twiddles of a mixed-radix transform of runtime length, its roots of
+/// unity being stored in `S`
+pub struct Stages<T, S> {
+    /// Radix of each stage, outermost first
+    factors: [usize; MAX_STAGES],
+    stages: usize,
+    /// The roots `W_len^i` for `i` in `0..len`, of which every twiddle of every stage is one
+    roots: S,
+    /// Bluestein transforms of the prime factors above [`MAX_DIRECT_RADIX`]
+    #[cfg(feature = "alloc")]
+    chirps: Vec<(usize, Chirp<T>)>,
+    element_marker: PhantomData<T>,
+}
+
+/// The roots `W_len^i` for `i` in `0..len` making up the table of [`Stages`]
+pub fn roots<T>(len: usize) -> impl Iterator<Item = T>
+where
+    T: Copy + ImgUnit + ComplexFloat + Mul<Scalar<T>, Output = T>,
+{
+    (0..len).map(move |i| calculate_twiddle::<T>(i, len))
+}
+
+impl<T, S> Stages<T, S>
+where
+    T: Copy
+        + Add<Output = T>
+        + Sub<Output = T>
+        + Mul<Scalar<T>, Output = T>
+        + ImgUnit
+        + ComplexFloat,
+    S: AsRef<[T]>,
+{
+    pub fn factors(&self) -> &[usize] {
+        &self.factors[..self.stages]
+    }
+
+    /// Twiddle `W_n^(q * k)` of a stage of length `n`, which is `W_len^(q * k * len / n)`
+    #[inline]
+    fn twiddle(&self, n: usize, q: usize, k: usize) -> T {
+        let roots = self.roots.as_ref();
+        roots[q * k * (roots.len() / n)]
+    }
+
+    /// Root of unity `W_p^r` of the butterfly of a stage of radix `p`
+    #[inline]
+    fn root(&self, stage: usize, r: usize) -> T {
+        let roots = self.roots.as_ref();
+        roots[r * (roots.len() / self.factors[stage])]
+    }
+
+    /// Factorizes the length of `roots`, the table returned by [`roots`].
+    pub fn new(roots: S) -> Self {
+        let len = roots.as_ref().len();
+        let mut factors = [0; MAX_STAGES];
+        let mut stages = 0;
+        let mut remaining = len;
+        for radix in [4, 2, 3, 5, 7] {
+            while remaining > 1 && remaining % radix == 0 {
+                factors[stages] = radix;
+                stages += 1;
+                remaining /= radix;
+            }
+        }
+
+        // Whatever is left is made of prime factors above 7
+        let mut radix = 11;
+        while remaining > 1 {
+            if radix * radix > remaining {
+                radix = remaining;
+            }
+            while remaining % radix == 0 {
+                factors[stages] = radix;
+                stages += 1;
+                remaining /= radix;
+            }
+            radix += 2;
+        }
+
+        #[cfg(feature = "alloc")]
+        {
+            let mut chirps: Vec<(usize, Chirp<T>)> = Vec::new();
+            for p in factors[..stages].iter() {
+                if *p > MAX_DIRECT_RADIX && chirps.iter().all(|(q, _)| q != p) {
+                    chirps.push((*p, Chirp::new(*p)));
+                }
+            }
+
+            Self {
+                factors,
+                stages,
+                roots,
+                chirps,
+                element_marker: PhantomData,
+            }
+        }
+
+        #[cfg(not(feature = "alloc"))]
+        Self {
+            factors,
+            stages,
+            roots,
+            element_marker: PhantomData,
+        }
+    }
+
+    /// Decimation-in-time step over the `output.len()` samples of `input` starting at
+    /// `offset` with `stride`, writing their spectrum into `output`.
+    /// Once the sub-transforms are done, those input samples are reused as butterfly scratch.
+    fn transform(
+        &self,
+        input: &mut [T],
+        offset: usize,
+        stride: usize,
+        output: &mut [T],
+        stage: usize,
+        direction: Direction,
+    ) {
+        let n = output.len();
+        if stage == self.stages {
+            output[0] = input[offset];
+            return;
+        }
+
+        // 1. Sub-transforms of the p decimated sequences
+        let p = self.factors[stage];
+        let m = n / p;
+        for (q, sub_output) in output.chunks_exact_mut(m).enumerate() {
+            self.transform(
+                input,
+                offset + q * stride,
+                stride * p,
+                sub_output,
+                stage + 1,
+                direction,
+            );
+        }
+
+        // Inverse twiddles are the conjugates of the forward ones
+        let directed = |x: T| match direction {
+            Direction::Forward => x,
+            Direction::Inverse => x.conj(),
+        };
+
+        // 2. Twiddled radix-p butterflies
+        let scratch = |q: usize| offset + q * stride;
+        for k in 0..m {
+            input[scratch(0)] = output[k];
+            for q in 1..p {
+                input[scratch(q)] = output[q * m + k] * directed(self.twiddle(n, q, k));
+            }
+
+            match p {
+                2 => {
+                    let (t0, t1) = (input[scratch(0)], input[scratch(1)]);
+                    output[k] = t0 + t1;
+                    output[k + m] = t0 - t1;
+                }
+                4 => {
+                    let (t0, t1) = (input[scratch(0)], input[scratch(1)]);
+                    let (t2, t3) = (input[scratch(2)], input[scratch(3)]);
+                    let a0 = t0 + t2;
+                    let a1 = t0 - t2;
+                    let a2 = t1 + t3;
+                    let a3 = (t1 - t3) * directed(T::img_unit().conj());
+                    output[k] = a0 + a2;
+                    output[k + m] = a1 + a3;
+                    output[k + 2 * m] = a0 - a2;
+                    output[k + 3 * m] = a1 - a3;
+                }
+                3 => self.odd_butterfly(3, stage, n, input, &scratch, output, k, direction),
+                5 => self.odd_butterfly(5, stage, n, input, &scratch, output, k, direction),
+                7 => self.odd_butterfly(7, stage, n, input, &scratch, output, k, direction),
+                #[cfg(feature = "alloc")]
+                _ if p > MAX_DIRECT_RADIX => {
+                    let chirp = self
+                        .chirps
+                        .iter()
+                        .find_map(|(q, chirp)| (*q == p).then_some(chirp))
+                        .expect("every large factor has its chirp");
+                    let mut spectrum = vec![T::zero(); p];
+                    chirp.transform((0..p).map(|q| input[scratch(q)]), &mut spectrum, direction);
+                    for (r, x) in spectrum.into_iter().enumerate() {
+                        output[k + r * m] = x;
+                    }
+                }
+                _ => self.odd_butterfly(p, stage, n, input, &scratch, output, k, direction),
+            }
+        }
+    }
+
+    /// Radix-p butterfly for an odd p over the twiddled samples `input[scratch(q)]`,
+    /// writing `output[k + r * m]`. Pairing `q` with `p - q` turns the p² complex products
+    /// of the DFT into (p - 1)² / 2 real ones, and inlining it with a literal p unrolls
+    /// the dedicated radix-3, 5 and 7 butterflies.
+    #[inline(always)]
+    #[allow(clippy::too_many_arguments)]
+    fn odd_butterfly(
+        &self,
+        p: usize,
+        stage: usize,
+        n: usize,
+        input: &mut [T],
+        scratch: &impl Fn(usize) -> usize,
+        output: &mut [T],
+        k: usize,
+        direction: Direction,
+    ) {
+        let m = n / p;
+        let half = p / 2;
+        // Sums in the first half of the scratch, differences in the second
+        for q in 1..=half {
+            let (a, b) = (input[scratch(q)], input[scratch(p - q)]);
+            input[scratch(q)] = a + b;
+            input[scratch(p - q)] = a - b;
+        }
+
+        // -j forward, +j inverse
+        let rotation = match direction {
+            Direction::Forward => T::img_unit().conj(),
+            Direction::Inverse => T::img_unit(),
+        };
+        let x0 = input[scratch(0)];
+        output[k] = (1..=half).fold(x0, |acc, q| acc + input[scratch(q)]);
+        for r in 1..=half {
+            let mut real = x0;
+            let mut imaginary = T::zero();
+            for q in 1..=half {
+                // W_p^j = cos(2πj/p) - j sin(2πj/p)
+                let root = self.root(stage, (q * r) % p);
+                real = real + input[scratch(q)] * root.re();
+                imaginary = imaginary - input[scratch(p - q)] * root.im();
+            }
+            let imaginary = imaginary * rotation;
+            output[k + r * m] = real + imaginary;
+            output[k + (p - r) * m] = real - imaginary;
+        }
+    }
+}
+
+/// Stages of a transform of length `N`, its roots in a `Box` under `alloc` and in an array
+/// otherwise
+pub struct StageCache<T, const N: usize> {
+    #[cfg(feature = "alloc")]
+    stages: Stages<T, Box<[T]>>,
+    #[cfg(not(feature = "alloc"))]
+    stages: Stages<T, [T; N]>,
+}
+
+impl<T, const N: usize> Default for StageCache<T, N>
+where
+    T: Copy
+        + Add<Output = T>
+        + Sub<Output = T>
+        + Mul<Scalar<T>, Output = T>
+        + ImgUnit
+        + ComplexFloat,
+{
+    fn default() -> Self {
+        #[cfg(feature = "alloc")]
+        let roots = roots(N).collect();
+        #[cfg(not(feature = "alloc"))]
+        let roots = core::array::from_fn(|i| calculate_twiddle::<T>(i, N));
+
+        StageCache {
+            stages: Stages::new(roots),
+        }
+    }
+}
+
+impl<T, const N: usize, A> Implementation<T, N, A> for MixedRadix
+where
+    A: Allocator<T, N>,
+    T: Copy
+        + Add<Output = T>
+        + Sub<Output = T>
+        + Mul<Scalar<T>, Output = T>
+        + ImgUnit
+        + ComplexFloat,
+{
+    type Cache = StageCache<T, N>;
+
+    const ASSERT_SUPPORTED: () = assert!(N > 0, "MixedRadix requires N > 0");
+
+    fn transform(
+        v: impl IntoIterator<Item = T>,
+        spectrum: &mut A::Element,
+        direction: Direction,
+        cache: &Self::Cache,
+    ) {
+        // The input buffer doubles as butterfly scratch, so it is always copied
+        let mut buffer = A::allocate();
+        let buffer = buffer.as_mut();
+
+        for (i, x) in v.into_iter().enumerate() {
+            buffer[i] = x;
+        }
+
+        cache
+            .stages
+            .transform(buffer, 0, 1, spectrum.as_mut(), 0, direction);
+    }
+
+    fn transform_with_scratch(
+        buffer: &mut [T],
+        scratch: &mut [T],
+        direction: Direction,
+        cache: &Self::Cache,
+    ) {
+        scratch.copy_from_slice(buffer);
+        cache.stages.transform(scratch, 0, 1, buffer, 0, direction);
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T> DynImplementation<T> for MixedRadix
+where
+    T: Copy
+        + Add<Output = T>
+        + Sub<Output = T>
+        + Mul<Scalar<T>, Output = T>
+        + ImgUnit
+        + ComplexFloat,
+{
+    type Cache = Stages<T, Box<[T]>>;
+
+    fn cache(len: usize) -> Result<Self::Cache, Error> {
+        Ok(Stages::new(roots(len).collect()))
+    }
+
+    fn transform(
+        v: impl IntoIterator<Item = T>,
+        output: &mut [T],
+        direction: Direction,
+        cache: &Self::Cache,
+    ) {
+        let mut buffer = v.into_iter().collect::<Vec<_>>();
+        buffer.resize(output.len(), T::zero());
+        cache.transform(&mut buffer, 0, 1, output, 0, direction);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use num_complex::{Complex32, Complex64};
+
+    use crate::{
+        allocators::boxed::BoxedAllocator,
+        implementations::{
+            mixed_radix::{roots, Stages},
+            MixedRadix,
+        },
+        test::{
+            ComplexTestFixture, DoubleComplexTestFixture, SizedComplexTestFixture, TestFixture,
+        },
+    };
+
+    fn factors(len: usize) -> Vec<usize> {
+        Stages::<Complex32, Box<[_]>>::new(roots(len).collect())
+            .factors()
+            .to_vec()
+    }
+
+    #[test]
+    fn factorization_test() {
+        assert_eq!(factors(1), &[]);
+        assert_eq!(factors(480), &[4, 4, 2, 3, 5]);
+        assert_eq!(factors(3000), &[4, 2, 3, 5, 5, 5]);
+        assert_eq!(factors(1573), &[11, 11, 13]);
+        assert_eq!(factors(4099), &[4099]);
+        assert_eq!(factors(4757), &[67, 71]);
+    }
+
+    #[test]
+    fn impulse_test() {
+        ComplexTestFixture::<MixedRadix>::impulse_test();
+        SizedComplexTestFixture::<MixedRadix, 960>::impulse_test();
+    }
+
+    #[test]
+    fn linearity_test() {
+        ComplexTestFixture::<MixedRadix>::linearity_test();
+        SizedComplexTestFixture::<MixedRadix, 105>::linearity_test();
+    }
+
+    #[test]
+    fn ground_truth_test() {
+        ComplexTestFixture::<MixedRadix>::ground_truth_test();
+        SizedComplexTestFixture::<MixedRadix, 1>::ground_truth_test();
+        SizedComplexTestFixture::<MixedRadix, 49>::ground_truth_test();
+        SizedComplexTestFixture::<MixedRadix, 143>::ground_truth_test();
+        SizedComplexTestFixture::<MixedRadix, 480>::ground_truth_test();
+        SizedComplexTestFixture::<MixedRadix, 960>::ground_truth_test();
+        SizedComplexTestFixture::<MixedRadix, 1200>::ground_truth_test();
+        // Prime factor above the direct butterflies
+        SizedComplexTestFixture::<MixedRadix, 402>::ground_truth_test();
+        SizedComplexTestFixture::<MixedRadix, 1031>::ground_truth_test();
+    }
+
+    #[test]
+    fn round_trip_test() {
+        ComplexTestFixture::<MixedRadix>::round_trip_test();
+        SizedComplexTestFixture::<MixedRadix, 210>::round_trip_test();
+        SizedComplexTestFixture::<MixedRadix, 402>::round_trip_test();
+    }
+
+    #[test]
+    fn in_place_test() {
+        ComplexTestFixture::<MixedRadix>::in_place_test();
+    }
+
+    #[test]
+    fn double_precision_test() {
+        DoubleComplexTestFixture::<MixedRadix>::ground_truth_test();
+        DoubleComplexTestFixture::<MixedRadix>::round_trip_test();
+
+        // Two Bluestein stages, past what single precision Naive resolves
+        TestFixture::<Complex64, 4757, BoxedAllocator, MixedRadix>::ground_truth_test();
+    }
+
+    #[test]
+    fn inverse_ground_truth_test() {
+        ComplexTestFixture::<MixedRadix>::inverse_ground_truth_test();
+        SizedComplexTestFixture::<MixedRadix, 210>::inverse_ground_truth_test();
+    }
+}
@@ -1,42 +1,115 @@
 use crate::implementations::Naive;
-use crate::{Allocator, ImgUnit, Implementation, Scalar, PI};
+#[cfg(feature = "alloc")]
+use crate::{dynamic::DynImplementation, Error};
+use crate::{scalar, Allocator, Direction, ImgUnit, Implementation, Scalar};
+
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
 
 use core::ops::{Add, Mul, Sub};
 
-#[allow(unused_imports)]
-use micromath::F32Ext;
+use num_traits::{FloatConst, One};
 
 use crate::ComplexFloat;
 
+/// Direct DFT of `input` into `output`, both of the same length.
+pub(crate) fn dft<T>(input: &[T], output: &mut [T], direction: Direction)
+where
+    T: Copy
+        + Add<Output = T>
+        + Sub<Output = T>
+        + ImgUnit
+        + Mul<Scalar<T>, Output = T>
+        + ComplexFloat,
+{
+    let f_n = scalar::<Scalar<T>>(output.len());
+    let sign = match direction {
+        Direction::Forward => -Scalar::<T>::one(),
+        Direction::Inverse => Scalar::<T>::one(),
+    };
+    let unit = T::img_unit();
+
+    for (i, x) in output.iter_mut().enumerate() {
+        *x = T::zero();
+        for (j, y) in input.iter().enumerate() {
+            let omega =
+                sign * (scalar::<Scalar<T>>(2) * Scalar::<T>::PI() * scalar(i) * scalar(j)) / f_n;
+            *x = *x + *y * (unit * omega).exp();
+        }
+    }
+}
+
 impl<T, const N: usize, A> Implementation<T, N, A> for Naive
 where
     A: Allocator<T, N>,
-    T: Copy + Add<Output = T> + Sub<Output = T> + ImgUnit + Mul<Scalar, Output = T> + ComplexFloat,
+    T: Copy
+        + Add<Output = T>
+        + Sub<Output = T>
+        + ImgUnit
+        + Mul<Scalar<T>, Output = T>
+        + ComplexFloat,
 {
     type Cache = ();
-    fn fft(v: impl IntoIterator<Item = T>, spectrum: &mut A::Element, _cache: &Self::Cache) {
-        let f_n = N as Scalar;
+    fn transform(
+        v: impl IntoIterator<Item = T>,
+        spectrum: &mut A::Element,
+        direction: Direction,
+        _cache: &Self::Cache,
+    ) {
         let mut buffer = A::allocate();
-        let unit = T::img_unit();
         let buffer = buffer.as_mut();
 
         for (idx, x) in v.into_iter().enumerate() {
             buffer[idx] = x;
         }
 
-        let spectrum = spectrum.as_mut();
-        for (i, x) in spectrum.iter_mut().enumerate().take(N) {
-            for (j, y) in buffer.iter().enumerate().take(N) {
-                let omega = -(2.0 * PI * (i as Scalar) * (j as Scalar)) / f_n;
-                *x = *x + *y * (unit * omega).exp();
-            }
-        }
+        dft(buffer, spectrum.as_mut(), direction);
+    }
+
+    fn transform_with_scratch(
+        buffer: &mut [T],
+        scratch: &mut [T],
+        direction: Direction,
+        _cache: &Self::Cache,
+    ) {
+        scratch.copy_from_slice(buffer);
+        dft(scratch, buffer, direction);
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T> DynImplementation<T> for Naive
+where
+    T: Copy
+        + Add<Output = T>
+        + Sub<Output = T>
+        + ImgUnit
+        + Mul<Scalar<T>, Output = T>
+        + ComplexFloat,
+{
+    type Cache = ();
+
+    fn cache(_len: usize) -> Result<Self::Cache, Error> {
+        Ok(())
+    }
+
+    fn transform(
+        v: impl IntoIterator<Item = T>,
+        output: &mut [T],
+        direction: Direction,
+        _cache: &Self::Cache,
+    ) {
+        let buffer = v.into_iter().collect::<Vec<_>>();
+        dft(&buffer, output, direction);
     }
 }
 
 #[cfg(test)]
 mod test {
-    use crate::{implementations::Naive, test::ComplexTestFixture};
+    use crate::{
+        implementations::Naive,
+        test::{ComplexTestFixture, DoubleComplexTestFixture},
+    };
 
     #[test]
     fn impulse_test() {
@@ -52,4 +125,25 @@ mod test {
     fn ground_truth_test() {
         ComplexTestFixture::<Naive>::ground_truth_test();
     }
+
+    #[test]
+    fn round_trip_test() {
+        ComplexTestFixture::<Naive>::round_trip_test();
+    }
+
+    #[test]
+    fn in_place_test() {
+        ComplexTestFixture::<Naive>::in_place_test();
+    }
+
+    #[test]
+    fn double_precision_test() {
+        DoubleComplexTestFixture::<Naive>::ground_truth_test();
+        DoubleComplexTestFixture::<Naive>::round_trip_test();
+    }
+
+    #[test]
+    fn inverse_ground_truth_test() {
+        ComplexTestFixture::<Naive>::inverse_ground_truth_test();
+    }
 }
@@ -13,25 +13,65 @@ use core::{
 };
 
 use num_complex::{Complex, ComplexFloat};
-use num_traits::{One, Zero};
-use windows::Rect;
+use num_traits::{Float, NumCast, One, ToPrimitive, Zero};
+use windows::{Rect, WindowTable};
 
 use self::implementations::CooleyTukey;
 
 pub mod allocators;
+#[cfg(feature = "alloc")]
+pub mod convolution;
+#[cfg(feature = "alloc")]
+pub mod dynamic;
+pub mod fir;
+pub mod goertzel;
 pub mod implementations;
+pub mod multidimensional;
+#[cfg(feature = "alloc")]
+pub mod spectral;
+pub mod stft;
 pub mod windows;
 
+#[cfg(feature = "alloc")]
+pub use dynamic::DynEngine;
+
+mod real;
+
+/// Window applied to the samples before the transform.
+///
+/// Engines hold an instance of their window, so windows can carry parameters or
+/// coefficient tables; parameterless windows such as [`Rect`] are zero-sized.
 pub trait WindowFunction<T>
 where
     T: Copy,
 {
-    type ItemMapper<'a, TIter: IntoIterator<Item = &'a T>>: IntoIterator<Item = T>
+    /// Whether the window leaves samples untouched, letting engines skip it.
+    const IDENTITY: bool = false;
+
+    type ItemMapper<'w, 'a, TIter: IntoIterator<Item = &'a T>>: IntoIterator<Item = T>
+    where
+        T: 'a,
+        Self: 'w;
+    fn windowed<'w, 'a, const N: usize, TIter: IntoIterator<Item = &'a T>>(
+        &'w self,
+        v: TIter,
+    ) -> Self::ItemMapper<'w, 'a, TIter>
     where
         T: 'a;
-    fn windowed<'a, const N: usize, TIter: IntoIterator<Item = &'a T>>(
+
+    /// Applies the window to `buffer` in place, its length being the window length.
+    fn window_in_place(&self, buffer: &mut [T]);
+
+    /// Same as [`WindowFunction::windowed`], for a window length only known at run time.
+    type DynItemMapper<'w, 'a, TIter: IntoIterator<Item = &'a T>>: IntoIterator<Item = T>
+    where
+        T: 'a,
+        Self: 'w;
+    fn windowed_dyn<'w, 'a, TIter: IntoIterator<Item = &'a T>>(
+        &'w self,
         v: TIter,
-    ) -> Self::ItemMapper<'a, TIter>
+        len: usize,
+    ) -> Self::DynItemMapper<'w, 'a, TIter>
     where
         T: 'a;
 }
@@ -47,7 +87,66 @@ where
     T: Copy,
 {
     type Cache: Default;
-    fn fft(v: impl IntoIterator<Item = T>, spectrum: &mut A::Element, cache: &Self::Cache);
+
+    /// Post-monomorphization check that the implementation supports `N`, evaluated when
+    /// an [`Engine`] is built. Implementations restricted to some lengths override it
+    /// with a failing `assert!`, so that an invalid `(I, N)` pair does not compile:
+    ///
+    /// ```compile_fail
+    /// use fft::{allocators::array::ArrayAllocator, implementations::CooleyTukey, windows::Rect, Engine};
+    /// use num_complex::Complex32;
+    ///
+    /// let engine = Engine::<Complex32, 1000, CooleyTukey, Rect, ArrayAllocator>::new();
+    /// ```
+    const ASSERT_SUPPORTED: () = ();
+
+    fn transform(
+        v: impl IntoIterator<Item = T>,
+        output: &mut A::Element,
+        direction: Direction,
+        cache: &Self::Cache,
+    );
+
+    /// In-place transform of the N elements of `buffer`, using the N elements of `scratch`
+    /// as workspace if the implementation needs one.
+    fn transform_with_scratch(
+        buffer: &mut [T],
+        scratch: &mut [T],
+        direction: Direction,
+        cache: &Self::Cache,
+    );
+
+    /// In-place transform of the N elements of `buffer`. Scratch is only allocated by
+    /// implementations that cannot work in place.
+    fn transform_in_place(buffer: &mut [T], direction: Direction, cache: &Self::Cache) {
+        let mut scratch = A::allocate();
+        Self::transform_with_scratch(buffer, scratch.as_mut(), direction, cache);
+    }
+
+    fn fft(v: impl IntoIterator<Item = T>, spectrum: &mut A::Element, cache: &Self::Cache) {
+        Self::transform(v, spectrum, Direction::Forward, cache);
+    }
+
+    /// Unnormalized inverse transform, scaling is left to the caller.
+    fn ifft(spectrum: impl IntoIterator<Item = T>, output: &mut A::Element, cache: &Self::Cache) {
+        Self::transform(spectrum, output, Direction::Inverse, cache);
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Direction {
+    Forward,
+    Inverse,
+}
+
+/// Scaling applied to the output of [`Engine::ifft`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Scaling {
+    /// Multiply the output by 1/N, so that `ifft(fft(x)) == x`.
+    #[default]
+    Normalized,
+    /// Leave the output unscaled.
+    Unnormalized,
 }
 
 pub struct Engine<T, const N: usize, I, W, A>
@@ -55,12 +154,13 @@ where
     A: Allocator<T, N>,
     I: Implementation<T, N, A>,
     W: WindowFunction<T>,
-    T: Copy,
+    T: Copy + ComplexFloat,
 {
     impl_marker: PhantomData<I>,
     allocator_marker: PhantomData<A>,
-    window_marker: PhantomData<W>,
     element_marker: PhantomData<T>,
+    window: W,
+    window_table: WindowTable<Scalar<T>, N>,
     cache: <I as Implementation<T, N, A>>::Cache,
 }
 
@@ -75,19 +175,13 @@ where
     T: Copy
         + Add<Output = T>
         + Sub<Output = T>
-        + Mul<Scalar, Output = T>
+        + Mul<Scalar<T>, Output = T>
         + ImgUnit
         + ComplexFloat
         + Default,
 {
     fn default() -> Engine<T, N, CooleyTukey, Rect, DefaultAllocator> {
-        Engine {
-            impl_marker: PhantomData,
-            allocator_marker: PhantomData,
-            window_marker: PhantomData,
-            element_marker: PhantomData,
-            cache: <CooleyTukey as Implementation<T, N, DefaultAllocator>>::Cache::default(),
-        }
+        Engine::new()
     }
 }
 
@@ -96,18 +190,48 @@ where
     A: Allocator<T, N>,
     I: Implementation<T, N, A>,
     W: WindowFunction<T>,
-    T: Copy,
+    T: Copy + ComplexFloat + Mul<Scalar<T>, Output = T>,
 {
-    pub fn new() -> Engine<T, N, I, W, A> {
+    /// Builds the engine and its cache, with the default instance of the window.
+    ///
+    /// Fails to compile (post-monomorphization) if `I` does not support `N`,
+    /// see [`Implementation::ASSERT_SUPPORTED`].
+    pub fn new() -> Engine<T, N, I, W, A>
+    where
+        W: Default,
+    {
+        Engine::with_window(W::default())
+    }
+
+    /// Same as [`Engine::new`], for windows carrying parameters or coefficients.
+    ///
+    /// The N window coefficients are computed here once, alongside the implementation
+    /// cache, rather than on every transform (and not at all for identity windows).
+    pub fn with_window(window: W) -> Engine<T, N, I, W, A> {
+        #[allow(clippy::let_unit_value)]
+        let () = <I as Implementation<T, N, A>>::ASSERT_SUPPORTED;
+
         Engine {
             impl_marker: PhantomData,
             allocator_marker: PhantomData,
-            window_marker: PhantomData,
             element_marker: PhantomData,
+            window_table: WindowTable::new(&window),
+            window,
             cache: <I as Implementation<T, N, A>>::Cache::default(),
         }
     }
 
+    pub fn window(&self) -> &W {
+        &self.window
+    }
+
+    pub fn window_table(&self) -> &WindowTable<Scalar<T>, N> {
+        &self.window_table
+    }
+
+    /// # Panics
+    /// Panics if `spectrum` does not hold exactly N elements (only possible with
+    /// slice-backed allocators) or if `v` yields more than N samples.
     pub fn fft<'a, TIter: IntoIterator<Item = &'a T>>(
         &self,
         v: TIter,
@@ -115,10 +239,181 @@ where
     ) where
         T: 'a,
     {
-        <I as Implementation<T, N, A>>::fft(W::windowed::<N, TIter>(v), spectrum, &self.cache);
+        assert_len::<T, N>(spectrum.as_ref());
+        let samples = at_most::<N, _>(v.into_iter());
+        if W::IDENTITY {
+            <I as Implementation<T, N, A>>::fft(samples.copied(), spectrum, &self.cache);
+        } else {
+            <I as Implementation<T, N, A>>::fft(
+                samples.zip(self.window_table.iter()).map(|(x, w)| *x * w),
+                spectrum,
+                &self.cache,
+            );
+        }
+    }
+
+    /// # Panics
+    /// Same conditions as [`Engine::fft`], applied to `output` and `spectrum`.
+    pub fn ifft<'a, TIter: IntoIterator<Item = &'a T>>(
+        &self,
+        spectrum: TIter,
+        output: &mut <A as Allocator<T, N>>::Element,
+        scaling: Scaling,
+    ) where
+        T: 'a + ComplexFloat + Mul<Scalar<T>, Output = T>,
+    {
+        assert_len::<T, N>(output.as_ref());
+        let spectrum = at_most::<N, _>(spectrum.into_iter()).copied();
+        <I as Implementation<T, N, A>>::ifft(spectrum, output, &self.cache);
+        scale::<T, N>(output.as_mut(), scaling);
+    }
+
+    /// Windows and transforms `buffer` in place. Implementations that work in place
+    /// (e.g. [`CooleyTukey`]) allocate nothing.
+    ///
+    /// # Panics
+    /// Panics if `buffer` does not hold exactly N elements.
+    pub fn fft_in_place(&self, buffer: &mut [T]) {
+        assert_len::<T, N>(buffer);
+        if !W::IDENTITY {
+            self.window_table.apply(buffer);
+        }
+        <I as Implementation<T, N, A>>::transform_in_place(buffer, Direction::Forward, &self.cache);
+    }
+
+    /// Same as [`Engine::fft_in_place`], with caller-provided workspace so that no
+    /// implementation allocates.
+    ///
+    /// # Panics
+    /// Panics if `buffer` or `scratch` do not hold exactly N elements.
+    pub fn fft_in_place_with_scratch(&self, buffer: &mut [T], scratch: &mut [T]) {
+        assert_len::<T, N>(buffer);
+        assert_len::<T, N>(scratch);
+        if !W::IDENTITY {
+            self.window_table.apply(buffer);
+        }
+        <I as Implementation<T, N, A>>::transform_with_scratch(
+            buffer,
+            scratch,
+            Direction::Forward,
+            &self.cache,
+        );
+    }
+
+    /// # Panics
+    /// Panics if `buffer` does not hold exactly N elements.
+    pub fn ifft_in_place(&self, buffer: &mut [T], scaling: Scaling)
+    where
+        T: ComplexFloat + Mul<Scalar<T>, Output = T>,
+    {
+        assert_len::<T, N>(buffer);
+        <I as Implementation<T, N, A>>::transform_in_place(buffer, Direction::Inverse, &self.cache);
+        scale::<T, N>(buffer, scaling);
+    }
+
+    /// # Panics
+    /// Panics if `buffer` or `scratch` do not hold exactly N elements.
+    pub fn ifft_in_place_with_scratch(&self, buffer: &mut [T], scratch: &mut [T], scaling: Scaling)
+    where
+        T: ComplexFloat + Mul<Scalar<T>, Output = T>,
+    {
+        assert_len::<T, N>(buffer);
+        assert_len::<T, N>(scratch);
+        <I as Implementation<T, N, A>>::transform_with_scratch(
+            buffer,
+            scratch,
+            Direction::Inverse,
+            &self.cache,
+        );
+        scale::<T, N>(buffer, scaling);
     }
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Error {
+    /// The implementation cannot transform sequences of this length.
+    UnsupportedLength { len: usize },
+    /// A buffer does not have the length the engine was built for.
+    LengthMismatch { expected: usize, actual: usize },
+    /// Consecutive frames must be at least one sample apart.
+    InvalidHop { hop: usize },
+    /// Consecutive segments must overlap by fewer samples than they hold.
+    InvalidOverlap { overlap: usize },
+    /// The window shifted by the hop does not overlap-add to a constant.
+    NotCola { hop: usize },
+    /// Some samples are not covered by any nonzero part of the window shifted by the hop,
+    /// so they cannot be resynthesized.
+    NotNola { hop: usize },
+}
+
+impl core::fmt::Display for Error {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Error::UnsupportedLength { len } => {
+                write!(f, "length {len} is not supported by this implementation")
+            }
+            Error::LengthMismatch { expected, actual } => {
+                write!(f, "expected a buffer of {expected} elements, got {actual}")
+            }
+            Error::InvalidHop { hop } => write!(f, "hop size {hop} is not supported"),
+            Error::InvalidOverlap { overlap } => {
+                write!(f, "segment overlap of {overlap} samples is not supported")
+            }
+            Error::NotCola { hop } => write!(
+                f,
+                "window does not satisfy the constant overlap-add condition at hop size {hop}"
+            ),
+            Error::NotNola { hop } => write!(
+                f,
+                "window does not satisfy the nonzero overlap-add condition at hop size {hop}, \
+                 the signal cannot be resynthesized"
+            ),
+        }
+    }
+}
+
+impl core::error::Error for Error {}
+
+fn scale<T, const N: usize>(buffer: &mut [T], scaling: Scaling)
+where
+    T: Copy + ComplexFloat + Mul<Scalar<T>, Output = T>,
+{
+    if scaling == Scaling::Normalized {
+        let scale = Scalar::<T>::one() / scalar(N);
+        for x in buffer {
+            *x = *x * scale;
+        }
+    }
+}
+
+#[inline]
+fn assert_len<T, const N: usize>(buffer: &[T]) {
+    assert_eq!(
+        buffer.len(),
+        N,
+        "expected a buffer of N = {} elements, got {}",
+        N,
+        buffer.len()
+    );
+}
+
+/// Yields the items of `iter`, panicking as soon as it holds more than N, whatever the
+/// consumer does with them.
+#[inline]
+fn at_most<const N: usize, I: Iterator>(iter: I) -> impl Iterator<Item = I::Item> {
+    let mut iter = iter.fuse();
+    (0..N).map_while(move |i| {
+        let item = iter.next();
+        if i + 1 == N {
+            assert!(
+                item.is_none() || iter.next().is_none(),
+                "expected at most N = {N} samples"
+            );
+        }
+        item
+    })
+}
+
 pub trait ImgUnit {
     fn img_unit() -> Self;
 }
@@ -132,14 +427,11 @@ where
     }
 }
 
-#[cfg(not(feature = "precision"))]
-pub type Scalar = f32;
-
-#[cfg(feature = "precision")]
-pub type Scalar = f64;
+/// Real scalar type of the element type `T`, e.g. `f32` for both `Complex32` and `f32`.
+pub type Scalar<T> = <T as ComplexFloat>::Real;
 
-#[cfg(not(feature = "precision"))]
-pub const PI: Scalar = core::f32::consts::PI;
-
-#[cfg(feature = "precision")]
-pub const PI: Scalar = core::f64::consts::PI;
+/// Converts a literal or an index into the scalar type `R`.
+#[inline(always)]
+pub(crate) fn scalar<R: Float>(x: impl ToPrimitive) -> R {
+    <R as NumCast>::from(x).unwrap()
+}
@@ -0,0 +1,431 @@
+use core::ops::Mul;
+
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+#[cfg(feature = "alloc")]
+use crate::{dynamic::DynImplementation, DynEngine};
+use crate::{
+    windows::Rect, Allocator, ComplexFloat, Engine, Error, Implementation, Scalar, Scaling,
+};
+
+/// Side of the square tiles the transpose works on, small enough for a tile of the input
+/// and one of the output to stay in cache together.
+const TILE: usize = 16;
+
+/// Two-dimensional FFT of `ROWS` × `COLS` row-major grids, computed row-column: every row is
+/// transformed in place, then the grid is transposed so that columns are transformed as
+/// contiguous rows too, and transposed back.
+pub struct Engine2d<T, const ROWS: usize, const COLS: usize, I, A>
+where
+    A: Allocator<T, ROWS> + Allocator<T, COLS>,
+    I: Implementation<T, ROWS, A> + Implementation<T, COLS, A>,
+    T: Copy + ComplexFloat + Mul<Scalar<T>, Output = T>,
+{
+    rows: Engine<T, COLS, I, Rect, A>,
+    columns: Engine<T, ROWS, I, Rect, A>,
+}
+
+impl<T, const ROWS: usize, const COLS: usize, I, A> Default for Engine2d<T, ROWS, COLS, I, A>
+where
+    A: Allocator<T, ROWS> + Allocator<T, COLS>,
+    I: Implementation<T, ROWS, A> + Implementation<T, COLS, A>,
+    T: Copy + ComplexFloat + Mul<Scalar<T>, Output = T>,
+{
+    fn default() -> Engine2d<T, ROWS, COLS, I, A> {
+        Engine2d::new()
+    }
+}
+
+impl<T, const ROWS: usize, const COLS: usize, I, A> Engine2d<T, ROWS, COLS, I, A>
+where
+    A: Allocator<T, ROWS> + Allocator<T, COLS>,
+    I: Implementation<T, ROWS, A> + Implementation<T, COLS, A>,
+    T: Copy + ComplexFloat + Mul<Scalar<T>, Output = T>,
+{
+    /// Builds the engines of the rows and of the columns.
+    ///
+    /// Fails to compile (post-monomorphization) if `I` does not support `ROWS` or `COLS`.
+    pub fn new() -> Engine2d<T, ROWS, COLS, I, A> {
+        Engine2d {
+            rows: Engine::new(),
+            columns: Engine::new(),
+        }
+    }
+
+    /// Transforms the `ROWS * COLS` elements of `grid` in place.
+    ///
+    /// Returns [`Error::LengthMismatch`] if `grid` does not hold `ROWS * COLS` elements.
+    #[cfg(feature = "alloc")]
+    pub fn fft(&self, grid: &mut [T]) -> Result<(), Error> {
+        let mut scratch = vec![T::zero(); grid.len()];
+        self.fft_with_scratch(grid, &mut scratch)
+    }
+
+    /// Same as [`Engine2d::fft`], with a caller-provided transpose buffer of `ROWS * COLS`
+    /// elements so that nothing is allocated.
+    pub fn fft_with_scratch(&self, grid: &mut [T], scratch: &mut [T]) -> Result<(), Error> {
+        self.transform(grid, scratch, None)
+    }
+
+    /// Inverse of [`Engine2d::fft`], scaled by `1 / (ROWS * COLS)` if normalized.
+    #[cfg(feature = "alloc")]
+    pub fn ifft(&self, grid: &mut [T], scaling: Scaling) -> Result<(), Error> {
+        let mut scratch = vec![T::zero(); grid.len()];
+        self.ifft_with_scratch(grid, &mut scratch, scaling)
+    }
+
+    /// Same as [`Engine2d::ifft`], with a caller-provided transpose buffer.
+    pub fn ifft_with_scratch(
+        &self,
+        grid: &mut [T],
+        scratch: &mut [T],
+        scaling: Scaling,
+    ) -> Result<(), Error> {
+        self.transform(grid, scratch, Some(scaling))
+    }
+
+    fn transform(
+        &self,
+        grid: &mut [T],
+        scratch: &mut [T],
+        inverse: Option<Scaling>,
+    ) -> Result<(), Error> {
+        check_len(grid.len(), ROWS * COLS)?;
+        check_len(scratch.len(), ROWS * COLS)?;
+
+        for row in grid.chunks_exact_mut(COLS) {
+            match inverse {
+                None => self.rows.fft_in_place(row),
+                Some(scaling) => self.rows.ifft_in_place(row, scaling),
+            }
+        }
+        transpose(grid, scratch, ROWS, COLS);
+        for column in scratch.chunks_exact_mut(ROWS) {
+            match inverse {
+                None => self.columns.fft_in_place(column),
+                Some(scaling) => self.columns.ifft_in_place(column, scaling),
+            }
+        }
+        transpose(scratch, grid, COLS, ROWS);
+        Ok(())
+    }
+}
+
+/// Counterpart of [`Engine2d`] for grids of any number of dimensions, with a shape only
+/// known at run time. The last axis is contiguous (row-major order).
+///
+/// Each axis is transformed in turn; axes other than the last are first transposed tile by
+/// tile to make their lines contiguous.
+#[cfg(feature = "alloc")]
+pub struct DynEngineNd<T, I>
+where
+    I: DynImplementation<T>,
+    T: Copy + ComplexFloat + Mul<Scalar<T>, Output = T>,
+{
+    shape: Vec<usize>,
+    engines: Vec<DynEngine<T, I, Rect>>,
+}
+
+#[cfg(feature = "alloc")]
+impl<T, I> DynEngineNd<T, I>
+where
+    I: DynImplementation<T>,
+    T: Copy + ComplexFloat + Mul<Scalar<T>, Output = T>,
+{
+    /// Builds an engine per axis of `shape`, e.g. `[rows, cols]` for a 2D grid.
+    ///
+    /// Returns [`Error::UnsupportedLength`] if `shape` is empty or if `I` does not support
+    /// the length of one of the axes.
+    pub fn new(shape: &[usize]) -> Result<DynEngineNd<T, I>, Error> {
+        if shape.is_empty() {
+            return Err(Error::UnsupportedLength { len: 0 });
+        }
+        Ok(DynEngineNd {
+            shape: shape.to_vec(),
+            engines: shape
+                .iter()
+                .map(|len| DynEngine::new(*len))
+                .collect::<Result<_, _>>()?,
+        })
+    }
+
+    pub fn shape(&self) -> &[usize] {
+        &self.shape
+    }
+
+    /// Number of elements of the grid.
+    pub fn len(&self) -> usize {
+        self.shape.iter().product()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Transforms the grid in place along every axis.
+    ///
+    /// Returns [`Error::LengthMismatch`] if `grid` does not hold [`DynEngineNd::len`]
+    /// elements.
+    pub fn fft(&self, grid: &mut [T]) -> Result<(), Error> {
+        self.transform(grid, None)
+    }
+
+    /// Inverse of [`DynEngineNd::fft`], scaled by `1 / len()` if normalized.
+    pub fn ifft(&self, grid: &mut [T], scaling: Scaling) -> Result<(), Error> {
+        self.transform(grid, Some(scaling))
+    }
+
+    fn transform(&self, grid: &mut [T], inverse: Option<Scaling>) -> Result<(), Error> {
+        check_len(grid.len(), self.len())?;
+        let longest = self.shape.iter().copied().max().unwrap_or(0);
+        let mut line = vec![T::zero(); longest];
+        let mut scratch = Vec::new();
+
+        for (axis, engine) in self.engines.iter().enumerate() {
+            let len = self.shape[axis];
+            let inner = self.shape[axis + 1..].iter().product::<usize>();
+            let line = &mut line[..len];
+            let mut transform_lines = |lines: &mut [T]| {
+                for row in lines.chunks_exact_mut(len) {
+                    line.copy_from_slice(row);
+                    match inverse {
+                        None => engine.fft(line, row),
+                        Some(scaling) => engine.ifft(line, row, scaling),
+                    }
+                    .expect("lines have the length of their axis");
+                }
+            };
+
+            if inner == 1 {
+                transform_lines(grid);
+                continue;
+            }
+            // Each slab is a `len` × `inner` matrix whose columns are the lines of the axis
+            scratch.resize(len * inner, T::zero());
+            for slab in grid.chunks_exact_mut(len * inner) {
+                transpose(slab, &mut scratch, len, inner);
+                transform_lines(&mut scratch);
+                transpose(&scratch, slab, inner, len);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Writes the transpose of the `rows` × `cols` row-major matrix `input` into `output`, tile
+/// by tile so that both are read and written in cache-sized pieces.
+pub(crate) fn transpose<T: Copy>(input: &[T], output: &mut [T], rows: usize, cols: usize) {
+    for row_start in (0..rows).step_by(TILE) {
+        for col_start in (0..cols).step_by(TILE) {
+            for row in row_start..(row_start + TILE).min(rows) {
+                for col in col_start..(col_start + TILE).min(cols) {
+                    output[col * rows + row] = input[row * cols + col];
+                }
+            }
+        }
+    }
+}
+
+/// Moves the zero-frequency bin of the `rows` × `cols` row-major spectrum `grid` to the
+/// center, at `(rows / 2, cols / 2)`, swapping quadrants.
+///
+/// Returns [`Error::LengthMismatch`] if `grid` does not hold `rows * cols` elements.
+pub fn fftshift_2d<T>(grid: &mut [T], rows: usize, cols: usize) -> Result<(), Error> {
+    check_len(grid.len(), rows * cols)?;
+    for row in grid.chunks_exact_mut(cols.max(1)) {
+        row.rotate_right(cols / 2);
+    }
+    grid.rotate_right(rows / 2 * cols);
+    Ok(())
+}
+
+/// Inverse of [`fftshift_2d`], which differs from it for odd sizes.
+pub fn ifftshift_2d<T>(grid: &mut [T], rows: usize, cols: usize) -> Result<(), Error> {
+    check_len(grid.len(), rows * cols)?;
+    for row in grid.chunks_exact_mut(cols.max(1)) {
+        row.rotate_left(cols / 2);
+    }
+    grid.rotate_left(rows / 2 * cols);
+    Ok(())
+}
+
+fn check_len(actual: usize, expected: usize) -> Result<(), Error> {
+    if actual != expected {
+        return Err(Error::LengthMismatch { expected, actual });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use approx::assert_relative_eq;
+    use num_complex::Complex64;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    use crate::{
+        allocators::{array::ArrayAllocator, boxed::BoxedAllocator},
+        implementations::{CooleyTukey, MixedRadix},
+        Error, Scaling,
+    };
+
+    use super::{fftshift_2d, ifftshift_2d, transpose, DynEngineNd, Engine2d};
+
+    fn grid(seed: u64, len: usize) -> Vec<Complex64> {
+        let mut rng = StdRng::seed_from_u64(seed);
+        (0..len)
+            .map(|_| Complex64::new(rng.gen::<f64>() - 0.5, rng.gen::<f64>() - 0.5))
+            .collect()
+    }
+
+    /// DFT of a row-major grid of `shape` evaluated directly.
+    fn direct(input: &[Complex64], shape: &[usize]) -> Vec<Complex64> {
+        let index = |mut flat: usize| {
+            let mut index = vec![0; shape.len()];
+            for (i, len) in shape.iter().enumerate().rev() {
+                index[i] = flat % len;
+                flat /= len;
+            }
+            index
+        };
+        (0..input.len())
+            .map(|k| {
+                let frequency = index(k);
+                input
+                    .iter()
+                    .enumerate()
+                    .map(|(n, x)| {
+                        let phase = index(n)
+                            .iter()
+                            .zip(frequency.iter())
+                            .zip(shape.iter())
+                            .map(|((n, k), len)| (n * k) as f64 / *len as f64)
+                            .sum::<f64>();
+                        x * Complex64::from_polar(1.0, -2.0 * core::f64::consts::PI * phase)
+                    })
+                    .sum()
+            })
+            .collect()
+    }
+
+    fn assert_close(actual: &[Complex64], expected: &[Complex64]) {
+        assert_eq!(actual.len(), expected.len());
+        for (a, e) in actual.iter().zip(expected.iter()) {
+            assert_relative_eq!(a.re, e.re, epsilon = 1e-9);
+            assert_relative_eq!(a.im, e.im, epsilon = 1e-9);
+        }
+    }
+
+    #[test]
+    fn engine_2d_test() {
+        let input = grid(1, 8 * 32);
+        let expected = direct(&input, &[8, 32]);
+
+        // On the stack, with a caller-provided transpose buffer
+        let engine = Engine2d::<Complex64, 8, 32, CooleyTukey, ArrayAllocator>::new();
+        let mut output = input.clone();
+        let mut scratch = vec![Complex64::default(); input.len()];
+        engine.fft_with_scratch(&mut output, &mut scratch).unwrap();
+        assert_close(&output, &expected);
+        engine
+            .ifft_with_scratch(&mut output, &mut scratch, Scaling::Normalized)
+            .unwrap();
+        assert_close(&output, &input);
+
+        let engine = Engine2d::<Complex64, 8, 32, CooleyTukey, BoxedAllocator>::new();
+        let mut output = input.clone();
+        engine.fft(&mut output).unwrap();
+        assert_close(&output, &expected);
+        engine.ifft(&mut output, Scaling::Unnormalized).unwrap();
+        let scaled = input.iter().map(|x| x * 256.0).collect::<Vec<_>>();
+        assert_close(&output, &scaled);
+
+        assert_eq!(
+            engine.fft(&mut output[1..]),
+            Err(Error::LengthMismatch {
+                expected: 256,
+                actual: 255
+            })
+        );
+    }
+
+    #[test]
+    fn engine_nd_test() {
+        for shape in [
+            vec![12],
+            vec![8, 32],
+            vec![5, 3],
+            vec![4, 6, 5],
+            vec![3, 2, 2, 4],
+        ] {
+            let len = shape.iter().product();
+            let input = grid(2, len);
+            let engine = DynEngineNd::<Complex64, MixedRadix>::new(&shape).unwrap();
+            assert_eq!(engine.len(), len);
+            let mut output = input.clone();
+            engine.fft(&mut output).unwrap();
+            assert_close(&output, &direct(&input, &shape));
+            engine.ifft(&mut output, Scaling::Normalized).unwrap();
+            assert_close(&output, &input);
+        }
+
+        // Same as the const-shaped engine
+        let input = grid(3, 16 * 64);
+        let mut expected = input.clone();
+        Engine2d::<Complex64, 16, 64, CooleyTukey, BoxedAllocator>::new()
+            .fft(&mut expected)
+            .unwrap();
+        let mut output = input.clone();
+        DynEngineNd::<Complex64, CooleyTukey>::new(&[16, 64])
+            .unwrap()
+            .fft(&mut output)
+            .unwrap();
+        assert_close(&output, &expected);
+
+        assert_eq!(
+            DynEngineNd::<Complex64, CooleyTukey>::new(&[16, 12]).err(),
+            Some(Error::UnsupportedLength { len: 12 })
+        );
+        assert_eq!(
+            DynEngineNd::<Complex64, CooleyTukey>::new(&[]).err(),
+            Some(Error::UnsupportedLength { len: 0 })
+        );
+    }
+
+    #[test]
+    fn transpose_test() {
+        // Sizes that are not multiples of the tile
+        let (rows, cols) = (37, 19);
+        let input = (0..rows * cols).collect::<Vec<_>>();
+        let mut output = vec![0; rows * cols];
+        transpose(&input, &mut output, rows, cols);
+        for row in 0..rows {
+            for col in 0..cols {
+                assert_eq!(output[col * rows + row], input[row * cols + col]);
+            }
+        }
+    }
+
+    #[test]
+    fn fftshift_test() {
+        let mut grid = (0..12).collect::<Vec<_>>();
+        fftshift_2d(&mut grid, 3, 4).unwrap();
+        assert_eq!(grid, [10, 11, 8, 9, 2, 3, 0, 1, 6, 7, 4, 5]);
+        ifftshift_2d(&mut grid, 3, 4).unwrap();
+        assert_eq!(grid, (0..12).collect::<Vec<_>>());
+
+        // The zero-frequency bin lands at (rows / 2, cols / 2)
+        let mut grid = vec![0; 5 * 7];
+        grid[0] = 1;
+        fftshift_2d(&mut grid, 5, 7).unwrap();
+        assert_eq!(grid[2 * 7 + 3], 1);
+
+        assert_eq!(
+            fftshift_2d(&mut grid, 5, 6),
+            Err(Error::LengthMismatch {
+                expected: 30,
+                actual: 35
+            })
+        );
+    }
+}
@@ -0,0 +1,314 @@
+use core::ops::{Add, Mul, Sub};
+
+use num_traits::One;
+
+use crate::{
+    at_most,
+    implementations::{
+        cooley_tukey::{radix2_in_place, twiddle},
+        CooleyTukey,
+    },
+    scalar, Allocator, ComplexFloat, Direction, Engine, ImgUnit, Scalar, Scaling, WindowFunction,
+};
+
+impl<T, const N: usize, W, A> Engine<T, N, CooleyTukey, W, A>
+where
+    A: Allocator<T, N>,
+    W: WindowFunction<T>,
+    T: Copy
+        + Add<Output = T>
+        + Sub<Output = T>
+        + Mul<Scalar<T>, Output = T>
+        + ImgUnit
+        + ComplexFloat,
+{
+    /// Real-input FFT, writing the N/2 + 1 non-redundant bins into `spectrum`.
+    ///
+    /// The N real samples are packed into an N/2 point complex transform, so no scratch
+    /// buffer is needed. Inputs shorter than N are zero-padded.
+    ///
+    /// # Panics
+    /// Panics if `spectrum` holds fewer than N/2 + 1 bins or if `v` yields more than N
+    /// samples.
+    pub fn rfft<'a, TIter: IntoIterator<Item = &'a Scalar<T>>>(&self, v: TIter, spectrum: &mut [T])
+    where
+        Scalar<T>: 'a,
+    {
+        const { assert!(N >= 2, "rfft requires N >= 2") };
+        let half_n = N >> 1;
+        assert!(
+            spectrum.len() > half_n,
+            "rfft needs N/2 + 1 = {} output bins, got {}",
+            half_n + 1,
+            spectrum.len()
+        );
+
+        // 1. Pack even samples into the real part and odd samples into the imaginary part
+        spectrum[..half_n].fill(T::zero());
+        let samples = at_most::<N, _>(v.into_iter()).zip(self.window_table.iter());
+        for (i, x) in samples.map(|(x, w)| *x * w).enumerate() {
+            let x = if i & 1 == 0 {
+                T::one() * x
+            } else {
+                T::img_unit() * x
+            };
+            spectrum[i >> 1] = spectrum[i >> 1] + x;
+        }
+
+        // 2. N/2 point transform, whose twiddles are the even N point ones
+        radix2_in_place(
+            &mut spectrum[..half_n],
+            |i| twiddle::<T, N, A>(&self.cache, i, 2),
+            Direction::Forward,
+        );
+
+        let half = scalar::<Scalar<T>>(0.5);
+
+        // 3. Split the even/odd spectra and recombine them, X[k] and X[N/2 - k] at a time
+        for k in 0..=(half_n >> 1) {
+            let j = half_n - k;
+            let z_k = spectrum[k];
+            let z_j = spectrum[j % half_n].conj();
+            let even = (z_k + z_j) * half;
+            let odd = (z_k - z_j) * T::img_unit().conj() * half;
+            let odd = odd * twiddle::<T, N, A>(&self.cache, k, 1);
+            spectrum[k] = even + odd;
+            spectrum[j] = (even - odd).conj();
+        }
+    }
+
+    /// Inverse of [`Engine::rfft`], reading N/2 + 1 bins and writing N real samples.
+    ///
+    /// With [`Scaling::Unnormalized`] the output matches the real part of a full N point
+    /// unnormalized inverse transform. The packed spectrum is rebuilt in a buffer allocated
+    /// on every call, see [`Engine::irfft_with_scratch`] to provide it instead.
+    ///
+    /// # Panics
+    /// Panics if `output` holds fewer than N samples.
+    pub fn irfft<'a, TIter: IntoIterator<Item = &'a T>>(
+        &self,
+        spectrum: TIter,
+        output: &mut [Scalar<T>],
+        scaling: Scaling,
+    ) where
+        T: 'a,
+    {
+        let mut scratch = A::allocate();
+        self.irfft_with_scratch(spectrum, output, scratch.as_mut(), scaling);
+    }
+
+    /// Same as [`Engine::irfft`], with caller-provided workspace of at least N/2 + 1
+    /// elements so that nothing is allocated.
+    ///
+    /// # Panics
+    /// Panics if `output` holds fewer than N samples or `scratch` fewer than N/2 + 1.
+    pub fn irfft_with_scratch<'a, TIter: IntoIterator<Item = &'a T>>(
+        &self,
+        spectrum: TIter,
+        output: &mut [Scalar<T>],
+        scratch: &mut [T],
+        scaling: Scaling,
+    ) where
+        T: 'a,
+    {
+        const { assert!(N >= 2, "irfft requires N >= 2") };
+        let half_n = N >> 1;
+        assert!(
+            output.len() >= N,
+            "irfft needs N = {} output samples, got {}",
+            N,
+            output.len()
+        );
+        assert!(
+            scratch.len() > half_n,
+            "irfft needs N/2 + 1 = {} scratch elements, got {}",
+            half_n + 1,
+            scratch.len()
+        );
+
+        let buffer = &mut scratch[..=half_n];
+        buffer.fill(T::zero());
+        for (i, x) in spectrum.into_iter().take(half_n + 1).enumerate() {
+            buffer[i] = *x;
+        }
+
+        let half = scalar::<Scalar<T>>(0.5);
+
+        // 1. Rebuild the packed N/2 point spectrum, Z[k] and Z[N/2 - k] at a time
+        for k in 0..=(half_n >> 1) {
+            let j = half_n - k;
+            let x_k = buffer[k];
+            let x_j = buffer[j];
+            let even = (x_k + x_j.conj()) * half;
+            let odd = (x_k - x_j.conj()) * twiddle::<T, N, A>(&self.cache, k, 1).conj() * half;
+            buffer[k] = even + T::img_unit() * odd;
+            if j < half_n {
+                let odd = (x_j - x_k.conj()) * twiddle::<T, N, A>(&self.cache, j, 1).conj() * half;
+                buffer[j] = even.conj() + T::img_unit() * odd;
+            }
+        }
+
+        // 2. N/2 point inverse transform, then unpack the interleaved samples
+        radix2_in_place(
+            &mut buffer[..half_n],
+            |i| twiddle::<T, N, A>(&self.cache, i, 2),
+            Direction::Inverse,
+        );
+
+        let scale = match scaling {
+            Scaling::Normalized => Scalar::<T>::one() / scalar(half_n),
+            Scaling::Unnormalized => scalar(2),
+        };
+        for (i, z) in buffer.iter().take(half_n).enumerate() {
+            output[2 * i] = z.re() * scale;
+            output[2 * i + 1] = z.im() * scale;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use num_complex::Complex32;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    use crate::{
+        allocators::{array::ArrayAllocator, boxed::BoxedAllocator},
+        implementations::CooleyTukey,
+        test::array_assert_eq,
+        windows::{hanning::Hanning, Rect},
+        Allocator, Engine, Scaling, WindowFunction,
+    };
+
+    const N: usize = 32;
+    const SEEDS: [u64; 3] = [1234, 495611, 38596722];
+
+    fn real_ground_truth_test<W, A>()
+    where
+        A: Allocator<Complex32, N>,
+        W: WindowFunction<Complex32> + WindowFunction<f32> + Default,
+    {
+        let engine = Engine::<Complex32, N, CooleyTukey, W, A>::new();
+        for seed in SEEDS.iter() {
+            let mut rng = StdRng::seed_from_u64(*seed);
+            let v = (0..N).map(|_| rng.gen::<f32>()).collect::<Vec<_>>();
+            let complex_v = v
+                .iter()
+                .map(|x| Complex32::new(*x, 0.0))
+                .collect::<Vec<_>>();
+
+            let mut fft_v = A::allocate();
+            engine.fft(complex_v.as_slice(), &mut fft_v);
+            let mut rfft_v = A::allocate();
+            engine.rfft(v.as_slice(), rfft_v.as_mut());
+            array_assert_eq(&fft_v.as_ref()[..=N / 2], &rfft_v.as_ref()[..=N / 2], 1e-3);
+        }
+    }
+
+    fn real_round_trip_test<A: Allocator<Complex32, N>>() {
+        let engine = Engine::<Complex32, N, CooleyTukey, Rect, A>::new();
+        for seed in SEEDS.iter() {
+            let mut rng = StdRng::seed_from_u64(*seed);
+            let v = (0..N).map(|_| rng.gen::<f32>()).collect::<Vec<_>>();
+
+            let mut rfft_v = A::allocate();
+            engine.rfft(v.as_slice(), rfft_v.as_mut());
+            let mut round_trip = [0.0; N];
+            engine.irfft(rfft_v.as_ref(), &mut round_trip, Scaling::Normalized);
+            array_assert_eq(v.as_slice(), round_trip.as_slice(), 1e-4);
+
+            // Unnormalized output matches the full complex inverse transform
+            let mut ifft_v = A::allocate();
+            let mut full_spectrum = A::allocate();
+            engine.fft(
+                v.iter()
+                    .map(|x| Complex32::new(*x, 0.0))
+                    .collect::<Vec<_>>()
+                    .as_slice(),
+                &mut full_spectrum,
+            );
+            engine.ifft(full_spectrum.as_ref(), &mut ifft_v, Scaling::Unnormalized);
+            engine.irfft(rfft_v.as_ref(), &mut round_trip, Scaling::Unnormalized);
+            let ifft_re = ifft_v.as_ref().iter().map(|x| x.re).collect::<Vec<_>>();
+            array_assert_eq(ifft_re.as_slice(), round_trip.as_slice(), 1e-3);
+        }
+    }
+
+    #[test]
+    fn short_input_test() {
+        let engine = Engine::<Complex32, N, CooleyTukey, Hanning, ArrayAllocator>::new();
+        let mut rng = StdRng::seed_from_u64(1234);
+        let v = (0..N).map(|_| rng.gen::<f32>()).collect::<Vec<_>>();
+
+        // A previous transform must not leak into the zero padding
+        let mut spectrum = [Complex32::default(); N / 2 + 1];
+        engine.rfft(&v, &mut spectrum);
+        engine.rfft(&v[..N / 2 - 1], &mut spectrum);
+
+        let mut padded = [0.0; N];
+        padded[..N / 2 - 1].copy_from_slice(&v[..N / 2 - 1]);
+        let mut expected = [Complex32::default(); N / 2 + 1];
+        engine.rfft(&padded, &mut expected);
+        array_assert_eq(&expected, &spectrum, 1e-6);
+    }
+
+    #[test]
+    fn scratch_test() {
+        let engine = Engine::<Complex32, N, CooleyTukey, Rect, ArrayAllocator>::new();
+        let mut rng = StdRng::seed_from_u64(1234);
+        let v = (0..N).map(|_| rng.gen::<f32>()).collect::<Vec<_>>();
+        let mut spectrum = [Complex32::default(); N / 2 + 1];
+        engine.rfft(&v, &mut spectrum);
+
+        // Leftovers in the scratch, including past the bins read, do not matter
+        let mut scratch = [Complex32::new(7.0, -3.0); N / 2 + 1];
+        let mut round_trip = [0.0; N];
+        engine.irfft_with_scratch(
+            &spectrum[..N / 2],
+            &mut round_trip,
+            &mut scratch,
+            Scaling::Normalized,
+        );
+        let mut expected = [0.0; N];
+        engine.irfft(&spectrum[..N / 2], &mut expected, Scaling::Normalized);
+        array_assert_eq(&expected, &round_trip, 1e-6);
+
+        engine.irfft_with_scratch(
+            &spectrum,
+            &mut round_trip,
+            &mut scratch,
+            Scaling::Normalized,
+        );
+        array_assert_eq(v.as_slice(), round_trip.as_slice(), 1e-4);
+    }
+
+    #[test]
+    #[should_panic(expected = "irfft needs N/2 + 1 = 17 scratch elements, got 16")]
+    fn short_scratch_test() {
+        let engine = Engine::<Complex32, N, CooleyTukey, Rect, ArrayAllocator>::new();
+        let spectrum = [Complex32::default(); N / 2 + 1];
+        let mut scratch = [Complex32::default(); N / 2];
+        engine.irfft_with_scratch(&spectrum, &mut [0.0; N], &mut scratch, Scaling::Normalized);
+    }
+
+    #[test]
+    fn boxed_ground_truth_test() {
+        real_ground_truth_test::<Rect, BoxedAllocator>();
+        real_ground_truth_test::<Hanning, BoxedAllocator>();
+    }
+
+    #[test]
+    fn array_ground_truth_test() {
+        real_ground_truth_test::<Rect, ArrayAllocator>();
+        real_ground_truth_test::<Hanning, ArrayAllocator>();
+    }
+
+    #[test]
+    fn boxed_round_trip_test() {
+        real_round_trip_test::<BoxedAllocator>();
+    }
+
+    #[test]
+    fn array_round_trip_test() {
+        real_round_trip_test::<ArrayAllocator>();
+    }
+}
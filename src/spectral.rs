@@ -0,0 +1,202 @@
+use core::{cmp::Ordering, ops::Mul};
+
+use alloc::vec::Vec;
+use num_traits::{Float, One, Zero};
+
+use crate::{
+    scalar, Allocator, ComplexFloat, Engine, Error, Implementation, Scalar, WindowFunction,
+};
+
+pub mod cross;
+pub mod multitaper;
+pub mod welch;
+
+/// Which half of the spectrum an estimate covers.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Sides {
+    /// Frequencies from 0 to the Nyquist frequency, each positive frequency holding the
+    /// power of its negative counterpart too.
+    #[default]
+    OneSided,
+    /// Every bin of the transform, in its order (negative frequencies last).
+    TwoSided,
+}
+
+/// Power spectral density estimate, in squared signal units per Hz (or squared units for
+/// power spectra, see [`welch::PsdScaling`]).
+#[derive(Clone, Debug, PartialEq)]
+pub struct Psd<R> {
+    frequencies: Vec<R>,
+    density: Vec<R>,
+}
+
+impl<R: Float> Psd<R> {
+    /// Builds the estimate from the `len` two-sided bins of `power`, already in squared
+    /// units per Hz, folding them for [`Sides::OneSided`].
+    pub(crate) fn from_two_sided(power: Vec<R>, sample_rate: R, sides: Sides) -> Psd<R> {
+        let len = power.len();
+        let frequencies = frequencies(len, sample_rate, sides);
+        let density = match sides {
+            Sides::TwoSided => power,
+            Sides::OneSided => (0..=len / 2)
+                .map(|k| {
+                    if k == 0 || 2 * k == len {
+                        power[k]
+                    } else {
+                        power[k] + power[len - k]
+                    }
+                })
+                .collect(),
+        };
+        Psd {
+            frequencies,
+            density,
+        }
+    }
+
+    /// Frequency of each bin, in Hz.
+    pub fn frequencies(&self) -> &[R] {
+        &self.frequencies
+    }
+
+    pub fn density(&self) -> &[R] {
+        &self.density
+    }
+}
+
+/// Frequency of each bin of a transform of `len` samples, in Hz: the first `len / 2 + 1`
+/// bins for [`Sides::OneSided`].
+pub(crate) fn frequencies<R: Float>(len: usize, sample_rate: R, sides: Sides) -> Vec<R> {
+    let resolution = sample_rate / scalar(len);
+    match sides {
+        Sides::TwoSided => (0..len)
+            .map(|k| {
+                if 2 * k < len {
+                    scalar::<R>(k) * resolution
+                } else {
+                    -scalar::<R>(len - k) * resolution
+                }
+            })
+            .collect(),
+        Sides::OneSided => (0..=len / 2).map(|k| scalar::<R>(k) * resolution).collect(),
+    }
+}
+
+/// Trend removed from each segment before it is windowed.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Detrend {
+    /// Keep the samples as they are.
+    None,
+    /// Subtract the mean of the segment.
+    #[default]
+    Constant,
+    /// Subtract the least-squares line through the segment.
+    Linear,
+}
+
+impl Detrend {
+    /// Removes the trend from `segment` in place.
+    pub fn apply<T>(self, segment: &mut [T])
+    where
+        T: Copy + ComplexFloat + Mul<Scalar<T>, Output = T>,
+    {
+        if self == Detrend::None || segment.is_empty() {
+            return;
+        }
+        let len = scalar::<Scalar<T>>(segment.len());
+        let mean = segment.iter().fold(T::zero(), |sum, x| sum + *x) * (Scalar::<T>::one() / len);
+        if self == Detrend::Constant || segment.len() == 1 {
+            segment.iter_mut().for_each(|x| *x = *x - mean);
+            return;
+        }
+
+        // Slope against indices centered on the middle of the segment, where the line
+        // goes through the mean
+        let center = (len - Scalar::<T>::one()) / scalar(2);
+        let offset = |i: usize| scalar::<Scalar<T>>(i) - center;
+        let spread =
+            (0..segment.len()).fold(Scalar::<T>::zero(), |sum, i| sum + offset(i) * offset(i));
+        let slope = segment
+            .iter()
+            .enumerate()
+            .fold(T::zero(), |sum, (i, x)| sum + *x * offset(i))
+            * (Scalar::<T>::one() / spread);
+        for (i, x) in segment.iter_mut().enumerate() {
+            *x = *x - mean - slope * offset(i);
+        }
+    }
+}
+
+/// Start of each segment of N samples spaced by `N - overlap` samples in a signal of
+/// `len` samples, dropping the samples past the last full segment.
+///
+/// Fails if segments would not advance, or if the signal is shorter than a segment.
+pub(crate) fn segment_starts<const N: usize>(
+    len: usize,
+    overlap: usize,
+) -> Result<impl Iterator<Item = usize>, Error> {
+    if overlap >= N {
+        return Err(Error::InvalidOverlap { overlap });
+    }
+    if len < N {
+        return Err(Error::LengthMismatch {
+            expected: N,
+            actual: len,
+        });
+    }
+    Ok((0..=len - N).step_by(N - overlap))
+}
+
+/// Copies the N samples of `signal` from `start` into `buffer`, removes their trend, then
+/// windows and transforms them with `engine`.
+pub(crate) fn segment_spectrum<S, T, const N: usize, I, W, A>(
+    engine: &Engine<T, N, I, W, A>,
+    signal: &[S],
+    start: usize,
+    detrend: Detrend,
+    buffer: &mut [T],
+) where
+    A: Allocator<T, N>,
+    I: Implementation<T, N, A>,
+    W: WindowFunction<T>,
+    S: Copy + Into<T>,
+    T: Copy + ComplexFloat + Mul<Scalar<T>, Output = T>,
+{
+    for (x, s) in buffer.iter_mut().zip(signal[start..start + N].iter()) {
+        *x = (*s).into();
+    }
+    detrend.apply(buffer);
+    engine.fft_in_place(buffer);
+}
+
+/// Sum of the window coefficients and sum of their squares, which scale power spectra and
+/// densities respectively.
+pub(crate) fn window_sums<R: Float>(coefficients: impl Iterator<Item = R>) -> (R, R) {
+    coefficients.fold((R::zero(), R::zero()), |(sum, squares), w| {
+        (sum + w, squares + w * w)
+    })
+}
+
+pub(crate) fn median<R: Float>(values: &mut [R]) -> R {
+    values.sort_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+    let middle = values.len() / 2;
+    if values.len() % 2 == 1 {
+        values[middle]
+    } else {
+        (values[middle - 1] + values[middle]) / scalar(2)
+    }
+}
+
+/// Ratio of the median to the mean of `count` periodogram values, which follow a
+/// chi-squared distribution with 2 degrees of freedom (`1 - 1/2 + 1/3 - ...` over the
+/// odd count nearest below).
+pub(crate) fn median_bias<R: Float>(count: usize) -> R {
+    (1..=(count.saturating_sub(1)) / 2).fold(R::one(), |bias, i| {
+        bias + R::one() / scalar(2 * i + 1) - R::one() / scalar(2 * i)
+    })
+}
+
+pub(crate) fn sqr_norm<T: ComplexFloat>(x: T) -> Scalar<T> {
+    let norm = x.abs();
+    norm * norm
+}
@@ -0,0 +1,565 @@
+use core::{fmt, ops::Mul};
+
+use alloc::vec::Vec;
+use num_traits::{Float, One, Zero};
+
+use crate::{
+    scalar, Allocator, ComplexFloat, Engine, Error, ImgUnit, Implementation, Scalar, WindowFunction,
+};
+
+use super::{
+    frequencies, median, median_bias, segment_spectrum, segment_starts, sqr_norm,
+    welch::{Average, PsdOptions, PsdScaling},
+    window_sums, Detrend, Sides,
+};
+
+/// Cross spectral density of two channels, `conj(X) Y` averaged over segments, in the
+/// product of their units per Hz (or that product for [`PsdScaling::Spectrum`]).
+#[derive(Clone, PartialEq)]
+pub struct CrossSpectrum<T: ComplexFloat> {
+    frequencies: Vec<Scalar<T>>,
+    density: Vec<T>,
+}
+
+impl<T> fmt::Debug for CrossSpectrum<T>
+where
+    T: ComplexFloat + fmt::Debug,
+    Scalar<T>: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CrossSpectrum")
+            .field("frequencies", &self.frequencies)
+            .field("density", &self.density)
+            .finish()
+    }
+}
+
+impl<T: ComplexFloat> CrossSpectrum<T> {
+    /// Frequency of each bin, in Hz.
+    pub fn frequencies(&self) -> &[Scalar<T>] {
+        &self.frequencies
+    }
+
+    pub fn density(&self) -> &[T] {
+        &self.density
+    }
+}
+
+/// Magnitude-squared coherence of two channels, from 0 (unrelated) to 1 (linearly
+/// related without noise) at each frequency.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Coherence<R> {
+    frequencies: Vec<R>,
+    coherence: Vec<R>,
+}
+
+impl<R> Coherence<R> {
+    /// Frequency of each bin, in Hz.
+    pub fn frequencies(&self) -> &[R] {
+        &self.frequencies
+    }
+
+    pub fn coherence(&self) -> &[R] {
+        &self.coherence
+    }
+}
+
+/// Frequency response of a system estimated from its input and output.
+///
+/// `H1 = Pxy / Pxx` is unbiased by noise at the output and `H2 = Pyy / Pyx` by noise at
+/// the input; they agree where the coherence is close to 1.
+#[derive(Clone, PartialEq)]
+pub struct FrequencyResponse<T: ComplexFloat> {
+    frequencies: Vec<Scalar<T>>,
+    h1: Vec<T>,
+    h2: Vec<T>,
+    coherence: Vec<Scalar<T>>,
+}
+
+impl<T> fmt::Debug for FrequencyResponse<T>
+where
+    T: ComplexFloat + fmt::Debug,
+    Scalar<T>: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FrequencyResponse")
+            .field("frequencies", &self.frequencies)
+            .field("h1", &self.h1)
+            .field("h2", &self.h2)
+            .field("coherence", &self.coherence)
+            .finish()
+    }
+}
+
+impl<T: ComplexFloat> FrequencyResponse<T> {
+    /// Frequency of each bin, in Hz.
+    pub fn frequencies(&self) -> &[Scalar<T>] {
+        &self.frequencies
+    }
+
+    pub fn h1(&self) -> &[T] {
+        &self.h1
+    }
+
+    pub fn h2(&self) -> &[T] {
+        &self.h2
+    }
+
+    /// Coherence of the input and output, the quality of the estimates at each frequency.
+    pub fn coherence(&self) -> &[Scalar<T>] {
+        &self.coherence
+    }
+}
+
+/// Cross spectral density of `x` and `y`, real or complex, over the same segments as
+/// [`super::welch::welch`] with the same options. The median is taken separately on
+/// the real and imaginary parts.
+///
+/// One-sided estimates keep the first N/2 + 1 bins with doubled power, which holds the
+/// whole cross spectrum of real signals only.
+///
+/// Returns [`Error::LengthMismatch`] if `y` is not as long as `x`, or if they are shorter
+/// than N samples, and [`Error::InvalidOverlap`] if the overlap is not smaller than N.
+pub fn csd<S, T, const N: usize, I, W, A>(
+    engine: &Engine<T, N, I, W, A>,
+    x: &[S],
+    y: &[S],
+    options: &PsdOptions<Scalar<T>>,
+) -> Result<CrossSpectrum<T>, Error>
+where
+    A: Allocator<T, N>,
+    I: Implementation<T, N, A>,
+    W: WindowFunction<T>,
+    S: Copy + Into<T>,
+    T: Copy + ComplexFloat + Mul<Scalar<T>, Output = T> + ImgUnit,
+{
+    let mut cross = Vec::new();
+    for_each_segment(engine, x, y, options, |spectrum_x, spectrum_y| {
+        cross.push(
+            spectrum_x
+                .iter()
+                .zip(spectrum_y.iter())
+                .map(|(a, b)| a.conj() * *b)
+                .collect::<Vec<_>>(),
+        );
+    })?;
+
+    let count = cross.len();
+    let averaged = match options.average {
+        Average::Mean => (0..N)
+            .map(|k| {
+                cross.iter().fold(T::zero(), |sum, c| sum + c[k])
+                    * (Scalar::<T>::one() / scalar(count))
+            })
+            .collect::<Vec<_>>(),
+        Average::Median => {
+            let bias = median_bias::<Scalar<T>>(count);
+            let mut re = Vec::with_capacity(count);
+            let mut im = Vec::with_capacity(count);
+            (0..N)
+                .map(|k| {
+                    re.clear();
+                    im.clear();
+                    re.extend(cross.iter().map(|c| c[k].re()));
+                    im.extend(cross.iter().map(|c| c[k].im()));
+                    (T::one() * median(&mut re) + T::img_unit() * median(&mut im))
+                        * (Scalar::<T>::one() / bias)
+                })
+                .collect::<Vec<_>>()
+        }
+    };
+
+    let (sum, squares) = window_sums(engine.window_table().iter());
+    let factor = match options.scaling {
+        PsdScaling::Density => Scalar::<T>::one() / (options.sample_rate * squares),
+        PsdScaling::Spectrum => Scalar::<T>::one() / (sum * sum),
+    };
+    let density = match options.sides {
+        Sides::TwoSided => averaged.into_iter().map(|c| c * factor).collect(),
+        Sides::OneSided => (0..=N / 2)
+            .map(|k| {
+                if k == 0 || 2 * k == N {
+                    averaged[k] * factor
+                } else {
+                    averaged[k] * (factor + factor)
+                }
+            })
+            .collect(),
+    };
+    Ok(CrossSpectrum {
+        frequencies: frequencies(N, options.sample_rate, options.sides),
+        density,
+    })
+}
+
+/// Magnitude-squared coherence `|Pxy|² / (Pxx Pyy)` of `x` and `y`, real or complex, over
+/// the same segments as [`super::welch::welch`]. Spectra are always mean averaged and the
+/// scaling option is not used.
+///
+/// Needs several segments: a single one always gives a coherence of 1.
+///
+/// Returns the same errors as [`csd`].
+pub fn coherence<S, T, const N: usize, I, W, A>(
+    engine: &Engine<T, N, I, W, A>,
+    x: &[S],
+    y: &[S],
+    options: &PsdOptions<Scalar<T>>,
+) -> Result<Coherence<Scalar<T>>, Error>
+where
+    A: Allocator<T, N>,
+    I: Implementation<T, N, A>,
+    W: WindowFunction<T>,
+    S: Copy + Into<T>,
+    T: Copy + ComplexFloat + Mul<Scalar<T>, Output = T>,
+{
+    let spectra = Spectra::average(engine, x, y, options)?;
+    Ok(Coherence {
+        frequencies: frequencies(N, options.sample_rate, options.sides),
+        coherence: spectra
+            .bins(options.sides)
+            .map(|k| spectra.coherence(k))
+            .collect(),
+    })
+}
+
+/// H1 and H2 estimates of the frequency response from `input` to `output`, real or complex,
+/// with their coherence, over the same segments as [`super::welch::welch`]. Spectra are
+/// always mean averaged and the scaling option is not used.
+///
+/// Detrending removes the mean of every segment, which leaves nothing to estimate the
+/// response at f = 0 from: unless `options.detrend` is [`Detrend::None`], H1 and H2 are NaN
+/// in the DC bin.
+///
+/// Returns the same errors as [`csd`].
+pub fn frequency_response<S, T, const N: usize, I, W, A>(
+    engine: &Engine<T, N, I, W, A>,
+    input: &[S],
+    output: &[S],
+    options: &PsdOptions<Scalar<T>>,
+) -> Result<FrequencyResponse<T>, Error>
+where
+    A: Allocator<T, N>,
+    I: Implementation<T, N, A>,
+    W: WindowFunction<T>,
+    S: Copy + Into<T>,
+    T: Copy + ComplexFloat + Mul<Scalar<T>, Output = T>,
+{
+    let spectra = Spectra::average(engine, input, output, options)?;
+    let bins = || spectra.bins(options.sides);
+    let undefined = T::one() * Scalar::<T>::nan();
+    let observed = |k: usize| k != 0 || options.detrend == Detrend::None;
+    Ok(FrequencyResponse {
+        frequencies: frequencies(N, options.sample_rate, options.sides),
+        h1: bins()
+            .map(|k| {
+                if observed(k) {
+                    spectra.xy[k] * (Scalar::<T>::one() / spectra.xx[k])
+                } else {
+                    undefined
+                }
+            })
+            .collect(),
+        h2: bins()
+            .map(|k| {
+                if observed(k) {
+                    T::one() * spectra.yy[k] / spectra.xy[k].conj()
+                } else {
+                    undefined
+                }
+            })
+            .collect(),
+        coherence: bins().map(|k| spectra.coherence(k)).collect(),
+    })
+}
+
+/// Auto and cross spectra of two channels, summed over segments. Their ratios do not
+/// depend on the scaling.
+struct Spectra<T: ComplexFloat> {
+    xx: Vec<Scalar<T>>,
+    yy: Vec<Scalar<T>>,
+    xy: Vec<T>,
+}
+
+impl<T> Spectra<T>
+where
+    T: Copy + ComplexFloat,
+{
+    fn average<S, const N: usize, I, W, A>(
+        engine: &Engine<T, N, I, W, A>,
+        x: &[S],
+        y: &[S],
+        options: &PsdOptions<Scalar<T>>,
+    ) -> Result<Spectra<T>, Error>
+    where
+        A: Allocator<T, N>,
+        I: Implementation<T, N, A>,
+        W: WindowFunction<T>,
+        S: Copy + Into<T>,
+        T: Mul<Scalar<T>, Output = T>,
+    {
+        let mut spectra = Spectra {
+            xx: vec![Scalar::<T>::zero(); N],
+            yy: vec![Scalar::<T>::zero(); N],
+            xy: vec![T::zero(); N],
+        };
+        for_each_segment(engine, x, y, options, |spectrum_x, spectrum_y| {
+            for (k, (a, b)) in spectrum_x.iter().zip(spectrum_y.iter()).enumerate() {
+                spectra.xx[k] = spectra.xx[k] + sqr_norm(*a);
+                spectra.yy[k] = spectra.yy[k] + sqr_norm(*b);
+                spectra.xy[k] = spectra.xy[k] + a.conj() * *b;
+            }
+        })?;
+        Ok(spectra)
+    }
+
+    fn bins(&self, sides: Sides) -> core::ops::Range<usize> {
+        match sides {
+            Sides::OneSided => 0..self.xy.len() / 2 + 1,
+            Sides::TwoSided => 0..self.xy.len(),
+        }
+    }
+
+    fn coherence(&self, k: usize) -> Scalar<T> {
+        sqr_norm(self.xy[k]) / (self.xx[k] * self.yy[k])
+    }
+}
+
+/// Calls `f` with the detrended and windowed spectra of each pair of segments of `x` and
+/// `y`, the segmentation layer shared by every cross-channel estimate.
+fn for_each_segment<S, T, const N: usize, I, W, A>(
+    engine: &Engine<T, N, I, W, A>,
+    x: &[S],
+    y: &[S],
+    options: &PsdOptions<Scalar<T>>,
+    mut f: impl FnMut(&[T], &[T]),
+) -> Result<(), Error>
+where
+    A: Allocator<T, N>,
+    I: Implementation<T, N, A>,
+    W: WindowFunction<T>,
+    S: Copy + Into<T>,
+    T: Copy + ComplexFloat + Mul<Scalar<T>, Output = T>,
+{
+    if y.len() != x.len() {
+        return Err(Error::LengthMismatch {
+            expected: x.len(),
+            actual: y.len(),
+        });
+    }
+    let mut spectrum_x = A::allocate();
+    let mut spectrum_y = A::allocate();
+    for start in segment_starts::<N>(x.len(), options.overlap.unwrap_or(N / 2))? {
+        segment_spectrum(engine, x, start, options.detrend, spectrum_x.as_mut());
+        segment_spectrum(engine, y, start, options.detrend, spectrum_y.as_mut());
+        f(spectrum_x.as_ref(), spectrum_y.as_ref());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use approx::assert_relative_eq;
+    use num_complex::Complex64;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    use crate::{
+        allocators::boxed::BoxedAllocator,
+        implementations::CooleyTukey,
+        spectral::{
+            welch::{welch, Average, PsdOptions},
+            Detrend, Sides,
+        },
+        windows::hanning::Hanning,
+        Engine, Error,
+    };
+
+    use super::{coherence, csd, frequency_response};
+
+    const N: usize = 256;
+    const SAMPLE_RATE: f64 = 1000.0;
+
+    type TestEngine = Engine<Complex64, N, CooleyTukey, Hanning, BoxedAllocator>;
+
+    fn noise(seed: u64, len: usize, deviation: f64) -> Vec<f64> {
+        let mut rng = StdRng::seed_from_u64(seed);
+        // Uniform samples of variance deviation²
+        (0..len)
+            .map(|_| (rng.gen::<f64>() - 0.5) * deviation * 12f64.sqrt())
+            .collect()
+    }
+
+    /// `x` through the FIR filter `h`, starting from rest.
+    fn filter(x: &[f64], h: &[f64]) -> Vec<f64> {
+        (0..x.len())
+            .map(|n| {
+                h.iter()
+                    .enumerate()
+                    .filter(|(i, _)| *i <= n)
+                    .map(|(i, c)| c * x[n - i])
+                    .sum()
+            })
+            .collect()
+    }
+
+    /// Frequency response of the FIR filter `h` at `frequency` Hz.
+    fn response(h: &[f64], frequency: f64) -> Complex64 {
+        h.iter()
+            .enumerate()
+            .map(|(i, c)| {
+                Complex64::from_polar(
+                    *c,
+                    -2.0 * core::f64::consts::PI * frequency * i as f64 / SAMPLE_RATE,
+                )
+            })
+            .sum()
+    }
+
+    const FILTER: [f64; 4] = [0.5, 1.0, -0.25, 0.125];
+
+    #[test]
+    fn noiseless_response_test() {
+        let engine = TestEngine::new();
+        let input = noise(1, 64 * N, 1.0);
+        let output = filter(&input, &FILTER);
+        let options = PsdOptions::new(SAMPLE_RATE);
+        let estimate = frequency_response(&engine, &input, &output, &options).unwrap();
+        assert_eq!(estimate.frequencies().len(), N / 2 + 1);
+
+        for (k, frequency) in estimate.frequencies().iter().enumerate().skip(1) {
+            let expected = response(&FILTER, *frequency);
+            for h in [estimate.h1()[k], estimate.h2()[k]] {
+                assert_relative_eq!(h.re, expected.re, epsilon = 2e-2);
+                assert_relative_eq!(h.im, expected.im, epsilon = 2e-2);
+            }
+            assert!(estimate.coherence()[k] > 0.99);
+        }
+    }
+
+    #[test]
+    fn dc_response_test() {
+        let engine = TestEngine::new();
+        let input = noise(7, 64 * N, 1.0);
+        let output = filter(&input, &FILTER);
+
+        // The mean removed from each segment hides the DC gain
+        let options = PsdOptions::new(SAMPLE_RATE);
+        let estimate = frequency_response(&engine, &input, &output, &options).unwrap();
+        assert!(estimate.h1()[0].is_nan());
+        assert!(estimate.h2()[0].is_nan());
+        assert!(!estimate.h1()[1].is_nan());
+
+        // Without detrending it is the sum of the taps
+        let options = PsdOptions {
+            detrend: Detrend::None,
+            ..PsdOptions::new(SAMPLE_RATE)
+        };
+        let estimate = frequency_response(&engine, &input, &output, &options).unwrap();
+        let gain = FILTER.iter().sum::<f64>();
+        for h in [estimate.h1()[0], estimate.h2()[0]] {
+            assert_relative_eq!(h.re, gain, epsilon = 2e-2);
+            assert_relative_eq!(h.im, 0.0, epsilon = 2e-2);
+        }
+    }
+
+    #[test]
+    fn noisy_response_test() {
+        let engine = TestEngine::new();
+        let options = PsdOptions::new(SAMPLE_RATE);
+        let input = noise(2, 128 * N, 1.0);
+        let clean = filter(&input, &FILTER);
+        let magnitude = |h: &[Complex64]| {
+            h[1..N / 2].iter().map(|h| h.norm()).sum::<f64>() / (N / 2 - 1) as f64
+        };
+        let expected = (1..N / 2)
+            .map(|k| response(&FILTER, k as f64 * SAMPLE_RATE / N as f64).norm())
+            .sum::<f64>()
+            / (N / 2 - 1) as f64;
+
+        // Output noise leaves H1 unbiased and inflates H2
+        let output = clean
+            .iter()
+            .zip(noise(3, clean.len(), 0.5).iter())
+            .map(|(y, n)| y + n)
+            .collect::<Vec<_>>();
+        let estimate = frequency_response(&engine, &input, &output, &options).unwrap();
+        assert_relative_eq!(magnitude(estimate.h1()), expected, max_relative = 2e-2);
+        assert!(magnitude(estimate.h2()) > 1.1 * expected);
+        // Coherence is the share of the output power explained by the input
+        for (k, frequency) in estimate.frequencies().iter().enumerate().skip(1) {
+            let signal = response(&FILTER, *frequency).norm_sqr();
+            assert_relative_eq!(
+                estimate.coherence()[k],
+                signal / (signal + 0.25),
+                epsilon = 0.1
+            );
+        }
+
+        // Input noise leaves H2 unbiased and deflates H1
+        let measured = input
+            .iter()
+            .zip(noise(4, input.len(), 0.5).iter())
+            .map(|(x, n)| x + n)
+            .collect::<Vec<_>>();
+        let estimate = frequency_response(&engine, &measured, &clean, &options).unwrap();
+        assert_relative_eq!(magnitude(estimate.h2()), expected, max_relative = 2e-2);
+        assert!(magnitude(estimate.h1()) < 0.9 * expected);
+    }
+
+    #[test]
+    fn coherence_test() {
+        let engine = TestEngine::new();
+        let options = PsdOptions::new(SAMPLE_RATE);
+        let x = noise(5, 64 * N, 1.0);
+        let y = noise(6, 64 * N, 1.0);
+        // Unrelated channels: about 1 / segments
+        let unrelated = coherence(&engine, &x, &y, &options).unwrap();
+        let mean = unrelated.coherence().iter().sum::<f64>() / unrelated.coherence().len() as f64;
+        assert!(mean < 0.05);
+
+        let related = coherence(&engine, &x, &filter(&x, &FILTER), &options).unwrap();
+        assert_eq!(related.frequencies(), unrelated.frequencies());
+        assert!(related.coherence()[1..].iter().all(|c| *c > 0.99));
+    }
+
+    #[test]
+    fn csd_test() {
+        let engine = TestEngine::new();
+        let x = noise(7, 32 * N, 1.0);
+        for average in [Average::Mean, Average::Median] {
+            for sides in [Sides::OneSided, Sides::TwoSided] {
+                // The cross spectrum of a channel with itself is its PSD
+                let options = PsdOptions {
+                    average,
+                    sides,
+                    ..PsdOptions::new(SAMPLE_RATE)
+                };
+                let cross = csd(&engine, &x, &x, &options).unwrap();
+                let psd = welch(&engine, &x, &options).unwrap();
+                assert_eq!(cross.frequencies(), psd.frequencies());
+                for (c, p) in cross.density().iter().zip(psd.density().iter()) {
+                    assert_relative_eq!(c.re, *p, max_relative = 1e-12);
+                    assert_eq!(c.im, 0.0);
+                }
+            }
+        }
+
+        // A delay of d samples shows as a phase of -2π f d / fs
+        let delayed = filter(&x, &[0.0, 0.0, 1.0]);
+        let cross = csd(&engine, &x, &delayed, &PsdOptions::new(SAMPLE_RATE)).unwrap();
+        for (k, frequency) in cross.frequencies().iter().enumerate().take(N / 4).skip(1) {
+            assert_relative_eq!(
+                cross.density()[k].arg(),
+                -2.0 * core::f64::consts::PI * frequency * 2.0 / SAMPLE_RATE,
+                epsilon = 1e-2
+            );
+        }
+
+        assert_eq!(
+            csd(&engine, &x, &x[1..], &PsdOptions::new(SAMPLE_RATE)).err(),
+            Some(Error::LengthMismatch {
+                expected: x.len(),
+                actual: x.len() - 1
+            })
+        );
+    }
+}
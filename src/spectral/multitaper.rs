@@ -0,0 +1,336 @@
+use core::ops::Mul;
+
+use alloc::vec::Vec;
+use num_traits::{Float, One, Zero};
+
+use crate::{
+    scalar,
+    windows::{dpss::dpss, Rect},
+    Allocator, ComplexFloat, Engine, Error, Implementation, Scalar,
+};
+
+use super::{sqr_norm, Psd, Sides};
+
+/// Upper bound on the adaptive weighting iterations, which usually settle in a handful.
+const MAX_ADAPTIVE_ITERATIONS: usize = 100;
+
+/// How the eigenspectra of the tapers are combined.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Weighting {
+    /// Plain average of the K eigenspectra.
+    #[default]
+    Uniform,
+    /// Thomson's adaptive weights, frequency by frequency, which discount the tapers with
+    /// poor concentration where the spectrum is low and their leakage would dominate.
+    Adaptive,
+}
+
+/// Thomson's multitaper power spectral density estimator for records of N samples,
+/// averaging the periodograms of the record tapered by K orthogonal Slepian sequences.
+pub struct Multitaper<T, const N: usize, I, A>
+where
+    A: Allocator<T, N>,
+    I: Implementation<T, N, A>,
+    T: Copy + ComplexFloat + Mul<Scalar<T>, Output = T>,
+{
+    engine: Engine<T, N, I, Rect, A>,
+    tapers: Vec<Vec<Scalar<T>>>,
+    concentrations: Vec<Scalar<T>>,
+    sample_rate: Scalar<T>,
+    weighting: Weighting,
+}
+
+impl<T, const N: usize, I, A> Multitaper<T, N, I, A>
+where
+    A: Allocator<T, N>,
+    I: Implementation<T, N, A>,
+    T: Copy + ComplexFloat + Mul<Scalar<T>, Output = T>,
+{
+    /// Estimator using the first `count` tapers for the time-halfbandwidth product `nw`
+    /// (`count` is typically `2 nw - 1`), for samples taken at `sample_rate` Hz.
+    ///
+    /// # Panics
+    /// Panics if `count` is zero or larger than N, or if `nw` is not in `(0, N / 2)`.
+    pub fn new(
+        nw: f64,
+        count: usize,
+        sample_rate: Scalar<T>,
+        weighting: Weighting,
+    ) -> Multitaper<T, N, I, A> {
+        assert!(count > 0, "the estimate needs at least one taper");
+        let set = dpss(N, nw, count);
+        Multitaper {
+            engine: Engine::new(),
+            tapers: set
+                .tapers()
+                .iter()
+                .map(|taper| taper.iter().copied().map(scalar).collect())
+                .collect(),
+            concentrations: set.concentrations().iter().copied().map(scalar).collect(),
+            sample_rate,
+            weighting,
+        }
+    }
+
+    pub fn tapers(&self) -> &[Vec<Scalar<T>>] {
+        &self.tapers
+    }
+
+    pub fn sample_rate(&self) -> Scalar<T> {
+        self.sample_rate
+    }
+
+    pub fn weighting(&self) -> Weighting {
+        self.weighting
+    }
+
+    /// Estimates the PSD of the N samples of `signal`.
+    ///
+    /// Returns [`Error::LengthMismatch`] if `signal` does not hold N samples.
+    pub fn psd(&self, signal: &[T], sides: Sides) -> Result<Psd<Scalar<T>>, Error> {
+        check_len::<N>(signal.len())?;
+        Ok(self.estimate(|i| signal[i], sides))
+    }
+
+    /// Same as [`Multitaper::psd`] for real samples.
+    pub fn psd_real(&self, signal: &[Scalar<T>], sides: Sides) -> Result<Psd<Scalar<T>>, Error> {
+        check_len::<N>(signal.len())?;
+        Ok(self.estimate(|i| T::one() * signal[i], sides))
+    }
+
+    fn estimate(&self, sample: impl Fn(usize) -> T, sides: Sides) -> Psd<Scalar<T>> {
+        // Eigenspectra: periodograms of the record under each unit-energy taper
+        let mut buffer = A::allocate();
+        let eigenspectra = self
+            .tapers
+            .iter()
+            .map(|taper| {
+                let buffer = buffer.as_mut();
+                for (i, (x, w)) in buffer.iter_mut().zip(taper.iter()).enumerate() {
+                    *x = sample(i) * *w;
+                }
+                self.engine.fft_in_place(buffer);
+                buffer.iter().map(|x| sqr_norm(*x)).collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+
+        let power = match self.weighting {
+            Weighting::Uniform => {
+                let count = scalar::<Scalar<T>>(eigenspectra.len());
+                (0..N)
+                    .map(|k| {
+                        eigenspectra
+                            .iter()
+                            .fold(Scalar::<T>::zero(), |sum, s| sum + s[k])
+                            / count
+                    })
+                    .collect::<Vec<_>>()
+            }
+            Weighting::Adaptive => {
+                let variance = (0..N).fold(Scalar::<T>::zero(), |sum, i| sum + sqr_norm(sample(i)))
+                    / scalar(N);
+                (0..N)
+                    .map(|k| self.adaptive(&eigenspectra, k, variance))
+                    .collect::<Vec<_>>()
+            }
+        };
+
+        // Unit-energy tapers make the eigenspectra power per bin, spread over sample_rate / N
+        let power = power
+            .into_iter()
+            .map(|p| p / self.sample_rate)
+            .collect::<Vec<_>>();
+        Psd::from_two_sided(power, self.sample_rate, sides)
+    }
+
+    /// Adaptive estimate at bin `k` (Percival and Walden, eq. 370a), iterated from the
+    /// average of the first two eigenspectra.
+    fn adaptive(
+        &self,
+        eigenspectra: &[Vec<Scalar<T>>],
+        k: usize,
+        variance: Scalar<T>,
+    ) -> Scalar<T> {
+        let count = eigenspectra.len().min(2);
+        let mut estimate = eigenspectra[..count]
+            .iter()
+            .fold(Scalar::<T>::zero(), |sum, s| sum + s[k])
+            / scalar(count);
+        let tolerance = Float::sqrt(Scalar::<T>::epsilon());
+        for _ in 0..MAX_ADAPTIVE_ITERATIONS {
+            let (weighted, total) = eigenspectra.iter().zip(self.concentrations.iter()).fold(
+                (Scalar::<T>::zero(), Scalar::<T>::zero()),
+                |(weighted, total), (s, lambda)| {
+                    // d_k² with d_k = sqrt(λ_k) S / (λ_k S + (1 - λ_k) σ²)
+                    let denominator =
+                        *lambda * estimate + (Scalar::<T>::one() - *lambda) * variance;
+                    let weight = *lambda * estimate * estimate / (denominator * denominator);
+                    (weighted + weight * s[k], total + weight)
+                },
+            );
+            if total <= Scalar::<T>::zero() {
+                break;
+            }
+            let next = weighted / total;
+            let converged = Float::abs(next - estimate) <= tolerance * estimate;
+            estimate = next;
+            if converged {
+                break;
+            }
+        }
+        estimate
+    }
+}
+
+fn check_len<const N: usize>(len: usize) -> Result<(), Error> {
+    if len != N {
+        return Err(Error::LengthMismatch {
+            expected: N,
+            actual: len,
+        });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use approx::assert_relative_eq;
+    use num_complex::Complex64;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    use crate::{
+        allocators::boxed::BoxedAllocator, implementations::CooleyTukey, spectral::Sides, Error,
+    };
+
+    use super::{Multitaper, Weighting};
+
+    const N: usize = 256;
+    const SAMPLE_RATE: f64 = 1000.0;
+
+    type Estimator = Multitaper<Complex64, N, CooleyTukey, BoxedAllocator>;
+
+    fn noise(seed: u64, deviation: f64) -> Vec<f64> {
+        let mut rng = StdRng::seed_from_u64(seed);
+        // Uniform samples of variance deviation²
+        (0..N)
+            .map(|_| (rng.gen::<f64>() - 0.5) * deviation * 12f64.sqrt())
+            .collect()
+    }
+
+    fn tone(bin: f64, amplitude: f64) -> Vec<f64> {
+        (0..N)
+            .map(|n| amplitude * (2.0 * core::f64::consts::PI * bin * n as f64 / N as f64).cos())
+            .collect()
+    }
+
+    #[test]
+    fn white_noise_test() {
+        for weighting in [Weighting::Uniform, Weighting::Adaptive] {
+            let estimator = Estimator::new(4.0, 7, SAMPLE_RATE, weighting);
+            let mut mean = 0.0;
+            let mut total = 0.0;
+            for seed in 0..32 {
+                let signal = noise(seed, 2.0);
+                let two_sided = estimator.psd_real(&signal, Sides::TwoSided).unwrap();
+                mean += two_sided.density().iter().sum::<f64>() / N as f64 / 32.0;
+                let one_sided = estimator.psd_real(&signal, Sides::OneSided).unwrap();
+                total += one_sided.density().iter().sum::<f64>() * SAMPLE_RATE / N as f64 / 32.0;
+            }
+            // A flat density of σ² / fs, integrating to the variance σ²
+            assert_relative_eq!(mean, 4.0 / SAMPLE_RATE, max_relative = 5e-2);
+            assert_relative_eq!(total, 4.0, max_relative = 5e-2);
+        }
+    }
+
+    #[test]
+    fn sinusoid_test() {
+        let estimator = Estimator::new(4.0, 7, SAMPLE_RATE, Weighting::Uniform);
+        let psd = estimator
+            .psd_real(&tone(32.0, 3.0), Sides::OneSided)
+            .unwrap();
+        assert_eq!(psd.frequencies().len(), N / 2 + 1);
+        assert_relative_eq!(psd.frequencies()[N / 2], SAMPLE_RATE / 2.0);
+
+        let peak = (0..psd.density().len())
+            .max_by(|a, b| psd.density()[*a].total_cmp(&psd.density()[*b]))
+            .unwrap();
+        assert_relative_eq!(psd.frequencies()[peak], 125.0);
+        // The power of the tone, A² / 2, is spread over the bandwidth of the tapers
+        let power = psd.density().iter().sum::<f64>() * SAMPLE_RATE / N as f64;
+        assert_relative_eq!(power, 4.5, max_relative = 1e-2);
+    }
+
+    #[test]
+    fn complex_input_test() {
+        let estimator = Estimator::new(3.0, 5, SAMPLE_RATE, Weighting::Adaptive);
+        let real = noise(7, 1.0);
+        let complex = real
+            .iter()
+            .map(|x| Complex64::new(*x, 0.0))
+            .collect::<Vec<_>>();
+        for sides in [Sides::OneSided, Sides::TwoSided] {
+            assert_eq!(
+                estimator.psd_real(&real, sides).unwrap(),
+                estimator.psd(&complex, sides).unwrap()
+            );
+        }
+
+        // A complex exponential only shows at its positive frequency
+        let exponential = (0..N)
+            .map(|n| {
+                Complex64::from_polar(
+                    1.0,
+                    2.0 * core::f64::consts::PI * 64.0 * n as f64 / N as f64,
+                )
+            })
+            .collect::<Vec<_>>();
+        let psd = estimator.psd(&exponential, Sides::TwoSided).unwrap();
+        assert_relative_eq!(psd.frequencies()[N - 64], -250.0);
+        assert!(psd.density()[64] > 1e10 * psd.density()[N - 64]);
+    }
+
+    #[test]
+    fn adaptive_test() {
+        // Far from a strong tone in faint noise, adaptive weights shed the leakage of the
+        // least concentrated tapers
+        let signal = tone(40.5, 1.0)
+            .iter()
+            .zip(noise(3, 1e-5).iter())
+            .map(|(x, n)| x + n)
+            .collect::<Vec<_>>();
+        let uniform = Estimator::new(4.0, 7, SAMPLE_RATE, Weighting::Uniform)
+            .psd_real(&signal, Sides::OneSided)
+            .unwrap();
+        let adaptive = Estimator::new(4.0, 7, SAMPLE_RATE, Weighting::Adaptive)
+            .psd_real(&signal, Sides::OneSided)
+            .unwrap();
+        let far = |psd: &[f64]| psd[90..].iter().sum::<f64>();
+        assert!(far(adaptive.density()) < 1e-3 * far(uniform.density()));
+        // down to the noise floor of σ² / fs per bin (times two, one-sided)
+        assert!(far(adaptive.density()) < 10.0 * 39.0 * 2.0 * 1e-10 / SAMPLE_RATE);
+        // Around the tone both estimates agree
+        assert_relative_eq!(
+            uniform.density()[40],
+            adaptive.density()[40],
+            max_relative = 1e-2
+        );
+    }
+
+    #[test]
+    fn length_mismatch_test() {
+        let estimator = Estimator::new(4.0, 7, SAMPLE_RATE, Weighting::Uniform);
+        assert_eq!(
+            estimator.psd_real(&[0.0; N - 1], Sides::OneSided).err(),
+            Some(Error::LengthMismatch {
+                expected: N,
+                actual: N - 1
+            })
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "at least one taper")]
+    fn no_taper_test() {
+        Estimator::new(4.0, 0, SAMPLE_RATE, Weighting::Uniform);
+    }
+}
@@ -0,0 +1,387 @@
+use core::ops::Mul;
+
+use alloc::vec::Vec;
+use num_traits::{One, Zero};
+
+use crate::{
+    scalar, Allocator, ComplexFloat, Engine, Error, Implementation, Scalar, WindowFunction,
+};
+
+use super::{
+    median, median_bias, segment_spectrum, segment_starts, sqr_norm, window_sums, Detrend, Psd,
+    Sides,
+};
+
+/// Units of the estimate.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PsdScaling {
+    /// Power spectral density in squared units per Hz, whose integral over frequency is the
+    /// power of the signal; suited to broadband signals.
+    #[default]
+    Density,
+    /// Power spectrum in squared units, where a tone on a bin reads as its power
+    /// (`A² / 2` one-sided for a real tone of amplitude A); suited to narrowband signals.
+    Spectrum,
+}
+
+/// How the periodograms of the segments are combined.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Average {
+    #[default]
+    Mean,
+    /// Bias-corrected median, robust to segments hit by transients.
+    Median,
+}
+
+/// Options of [`periodogram`] and [`welch`]. Segments are N samples long, N being the length
+/// of the engine, and windowed by its window.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PsdOptions<R> {
+    /// Sample rate of the signal, in Hz.
+    pub sample_rate: R,
+    /// Samples shared by consecutive segments, half a segment if `None`.
+    pub overlap: Option<usize>,
+    pub detrend: Detrend,
+    pub average: Average,
+    pub scaling: PsdScaling,
+    pub sides: Sides,
+}
+
+impl<R> PsdOptions<R> {
+    /// Half-overlapping segments with their mean removed, averaged into a one-sided density.
+    pub fn new(sample_rate: R) -> PsdOptions<R> {
+        PsdOptions {
+            sample_rate,
+            overlap: None,
+            detrend: Detrend::Constant,
+            average: Average::Mean,
+            scaling: PsdScaling::Density,
+            sides: Sides::OneSided,
+        }
+    }
+}
+
+/// Periodogram of the N samples of `signal`, real or complex, windowed by the engine's
+/// window. Overlap and averaging options are not used.
+///
+/// Returns [`Error::LengthMismatch`] if `signal` does not hold N samples.
+pub fn periodogram<S, T, const N: usize, I, W, A>(
+    engine: &Engine<T, N, I, W, A>,
+    signal: &[S],
+    options: &PsdOptions<Scalar<T>>,
+) -> Result<Psd<Scalar<T>>, Error>
+where
+    A: Allocator<T, N>,
+    I: Implementation<T, N, A>,
+    W: WindowFunction<T>,
+    S: Copy + Into<T>,
+    T: Copy + ComplexFloat + Mul<Scalar<T>, Output = T>,
+{
+    if signal.len() != N {
+        return Err(Error::LengthMismatch {
+            expected: N,
+            actual: signal.len(),
+        });
+    }
+    let mut buffer = A::allocate();
+    segment_spectrum(engine, signal, 0, options.detrend, buffer.as_mut());
+    let power = buffer.as_ref().iter().map(|x| sqr_norm(*x)).collect();
+    Ok(scale(engine, power, options))
+}
+
+/// Welch's estimate: average of the periodograms of the segments of `signal`, real or
+/// complex, spaced by N minus the overlap samples. Samples past the last full segment
+/// are not used.
+///
+/// Returns [`Error::InvalidOverlap`] if the overlap is not smaller than N, and
+/// [`Error::LengthMismatch`] if `signal` is shorter than N samples.
+pub fn welch<S, T, const N: usize, I, W, A>(
+    engine: &Engine<T, N, I, W, A>,
+    signal: &[S],
+    options: &PsdOptions<Scalar<T>>,
+) -> Result<Psd<Scalar<T>>, Error>
+where
+    A: Allocator<T, N>,
+    I: Implementation<T, N, A>,
+    W: WindowFunction<T>,
+    S: Copy + Into<T>,
+    T: Copy + ComplexFloat + Mul<Scalar<T>, Output = T>,
+{
+    let starts = segment_starts::<N>(signal.len(), options.overlap.unwrap_or(N / 2))?;
+    let mut buffer = A::allocate();
+    let periodograms = starts
+        .map(|start| {
+            segment_spectrum(engine, signal, start, options.detrend, buffer.as_mut());
+            buffer
+                .as_ref()
+                .iter()
+                .map(|x| sqr_norm(*x))
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+
+    let count = periodograms.len();
+    let power = match options.average {
+        Average::Mean => (0..N)
+            .map(|k| {
+                periodograms
+                    .iter()
+                    .fold(Scalar::<T>::zero(), |sum, p| sum + p[k])
+                    / scalar(count)
+            })
+            .collect(),
+        Average::Median => {
+            let bias = median_bias::<Scalar<T>>(count);
+            let mut bin = Vec::with_capacity(count);
+            (0..N)
+                .map(|k| {
+                    bin.clear();
+                    bin.extend(periodograms.iter().map(|p| p[k]));
+                    median(&mut bin) / bias
+                })
+                .collect()
+        }
+    };
+    Ok(scale(engine, power, options))
+}
+
+/// Scales the two-sided power of a segment by the window gain into the requested units.
+fn scale<T, const N: usize, I, W, A>(
+    engine: &Engine<T, N, I, W, A>,
+    power: Vec<Scalar<T>>,
+    options: &PsdOptions<Scalar<T>>,
+) -> Psd<Scalar<T>>
+where
+    A: Allocator<T, N>,
+    I: Implementation<T, N, A>,
+    W: WindowFunction<T>,
+    T: Copy + ComplexFloat + Mul<Scalar<T>, Output = T>,
+{
+    let (sum, squares) = window_sums(engine.window_table().iter());
+    let factor = match options.scaling {
+        PsdScaling::Density => Scalar::<T>::one() / (options.sample_rate * squares),
+        PsdScaling::Spectrum => Scalar::<T>::one() / (sum * sum),
+    };
+    Psd::from_two_sided(
+        power.into_iter().map(|p| p * factor).collect(),
+        options.sample_rate,
+        options.sides,
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use approx::assert_relative_eq;
+    use num_complex::Complex64;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    use crate::{
+        allocators::boxed::BoxedAllocator,
+        implementations::{CooleyTukey, Naive},
+        spectral::{median_bias, Detrend, Sides},
+        windows::{hanning::Hanning, Rect},
+        Engine, Error,
+    };
+
+    use super::{periodogram, welch, Average, PsdOptions, PsdScaling};
+
+    const N: usize = 256;
+    const SAMPLE_RATE: f64 = 1000.0;
+
+    fn noise(seed: u64, len: usize, deviation: f64) -> Vec<f64> {
+        let mut rng = StdRng::seed_from_u64(seed);
+        // Uniform samples of variance deviation²
+        (0..len)
+            .map(|_| (rng.gen::<f64>() - 0.5) * deviation * 12f64.sqrt())
+            .collect()
+    }
+
+    fn tone(len: usize, frequency: f64, amplitude: f64) -> Vec<f64> {
+        (0..len)
+            .map(|n| {
+                amplitude * (2.0 * core::f64::consts::PI * frequency * n as f64 / SAMPLE_RATE).cos()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn white_noise_test() {
+        let engine = Engine::<Complex64, N, CooleyTukey, Hanning, BoxedAllocator>::new();
+        let signal = noise(1, 64 * N, 2.0);
+        for average in [Average::Mean, Average::Median] {
+            let options = PsdOptions {
+                average,
+                ..PsdOptions::new(SAMPLE_RATE)
+            };
+            let psd = welch(&engine, &signal, &options).unwrap();
+            assert_eq!(psd.density().len(), N / 2 + 1);
+            // Flat at 2 σ² / fs one-sided, away from the mean removed at DC
+            let mean = psd.density()[2..N / 2].iter().sum::<f64>() / (N / 2 - 2) as f64;
+            assert_relative_eq!(mean, 2.0 * 4.0 / SAMPLE_RATE, max_relative = 3e-2);
+
+            let options = PsdOptions {
+                sides: Sides::TwoSided,
+                ..options
+            };
+            let psd = welch(&engine, &signal, &options).unwrap();
+            let mean = psd.density()[2..N - 1].iter().sum::<f64>() / (N - 3) as f64;
+            assert_relative_eq!(mean, 4.0 / SAMPLE_RATE, max_relative = 3e-2);
+        }
+    }
+
+    #[test]
+    fn sinusoid_test() {
+        let engine = Engine::<Complex64, N, CooleyTukey, Hanning, BoxedAllocator>::new();
+        // On bin 32 of the segments
+        let frequency = 32.0 * SAMPLE_RATE / N as f64;
+        let signal = tone(8 * N, frequency, 3.0);
+
+        // The power spectrum reads the power of the tone at its bin
+        let options = PsdOptions {
+            scaling: PsdScaling::Spectrum,
+            ..PsdOptions::new(SAMPLE_RATE)
+        };
+        let spectrum = welch(&engine, &signal, &options).unwrap();
+        assert_relative_eq!(spectrum.frequencies()[32], frequency);
+        assert_relative_eq!(spectrum.density()[32], 4.5, max_relative = 1e-9);
+
+        // The density integrates to it
+        let density = welch(&engine, &signal, &PsdOptions::new(SAMPLE_RATE)).unwrap();
+        let power = density.density().iter().sum::<f64>() * SAMPLE_RATE / N as f64;
+        assert_relative_eq!(power, 4.5, max_relative = 1e-9);
+    }
+
+    #[test]
+    fn periodogram_test() {
+        const N: usize = 16;
+        let engine = Engine::<Complex64, N, Naive, Rect, BoxedAllocator>::new();
+        let signal = noise(2, N, 1.0)
+            .iter()
+            .zip(noise(3, N, 1.0).iter())
+            .map(|(re, im)| Complex64::new(*re, *im))
+            .collect::<Vec<_>>();
+        let options = PsdOptions {
+            detrend: Detrend::None,
+            sides: Sides::TwoSided,
+            ..PsdOptions::new(SAMPLE_RATE)
+        };
+        let psd = periodogram(&engine, &signal, &options).unwrap();
+        // |X_k|² / (fs N), with the DFT evaluated directly
+        for (k, density) in psd.density().iter().enumerate() {
+            let bin = signal
+                .iter()
+                .enumerate()
+                .map(|(n, x)| {
+                    x * Complex64::from_polar(
+                        1.0,
+                        -2.0 * core::f64::consts::PI * (k * n) as f64 / N as f64,
+                    )
+                })
+                .sum::<Complex64>();
+            assert_relative_eq!(
+                *density,
+                bin.norm_sqr() / (SAMPLE_RATE * N as f64),
+                max_relative = 1e-12
+            );
+        }
+        assert_relative_eq!(psd.frequencies()[N - 1], -SAMPLE_RATE / N as f64);
+
+        // A single segment without overlap is the periodogram
+        let options = PsdOptions {
+            overlap: Some(0),
+            ..options
+        };
+        assert_eq!(
+            welch(&engine, &signal, &options).unwrap(),
+            periodogram(&engine, &signal, &options).unwrap()
+        );
+
+        assert_eq!(
+            periodogram(&engine, &signal[1..], &options).err(),
+            Some(Error::LengthMismatch {
+                expected: N,
+                actual: N - 1
+            })
+        );
+    }
+
+    #[test]
+    fn detrend_test() {
+        let mut ramp = (0..8).map(|i| 2.0 + 0.5 * i as f64).collect::<Vec<_>>();
+        Detrend::Constant.apply(&mut ramp);
+        assert_relative_eq!(ramp.iter().sum::<f64>(), 0.0, epsilon = 1e-12);
+        assert_relative_eq!(ramp[7] - ramp[0], 3.5, epsilon = 1e-12);
+        Detrend::Linear.apply(&mut ramp);
+        for x in ramp.iter() {
+            assert_relative_eq!(*x, 0.0, epsilon = 1e-12);
+        }
+
+        // A drift buries the low bins unless it is removed
+        let engine = Engine::<Complex64, N, CooleyTukey, Hanning, BoxedAllocator>::new();
+        let signal = noise(4, 16 * N, 1e-3)
+            .iter()
+            .enumerate()
+            .map(|(i, x)| x + 1e-3 * i as f64)
+            .collect::<Vec<_>>();
+        let psd = |detrend| {
+            let options = PsdOptions {
+                detrend,
+                ..PsdOptions::new(SAMPLE_RATE)
+            };
+            welch(&engine, &signal, &options).unwrap().density()[1]
+        };
+        assert!(psd(Detrend::Linear) < 1e-3 * psd(Detrend::Constant));
+        assert!(psd(Detrend::Constant) < psd(Detrend::None));
+    }
+
+    #[test]
+    fn median_test() {
+        assert_relative_eq!(median_bias::<f64>(1), 1.0);
+        assert_relative_eq!(median_bias::<f64>(2), 1.0);
+        assert_relative_eq!(median_bias::<f64>(3), 1.0 - 0.5 + 1.0 / 3.0);
+        assert_relative_eq!(median_bias::<f64>(4), 1.0 - 0.5 + 1.0 / 3.0);
+
+        // A burst in one segment shifts the mean but hardly the median
+        let engine = Engine::<Complex64, N, CooleyTukey, Hanning, BoxedAllocator>::new();
+        let mut signal = noise(5, 32 * N, 1.0);
+        for x in signal[10 * N..11 * N].iter_mut() {
+            *x *= 100.0;
+        }
+        let level = |average| {
+            let options = PsdOptions {
+                average,
+                overlap: Some(0),
+                ..PsdOptions::new(SAMPLE_RATE)
+            };
+            let psd = welch(&engine, &signal, &options).unwrap();
+            psd.density()[2..N / 2].iter().sum::<f64>() / (N / 2 - 2) as f64
+        };
+        assert!(level(Average::Mean) > 100.0 * 2.0 / SAMPLE_RATE);
+        assert_relative_eq!(
+            level(Average::Median),
+            2.0 / SAMPLE_RATE,
+            max_relative = 0.1
+        );
+    }
+
+    #[test]
+    fn segmentation_test() {
+        let engine = Engine::<Complex64, N, CooleyTukey, Hanning, BoxedAllocator>::new();
+        let signal = noise(6, 4 * N, 1.0);
+        let options = PsdOptions {
+            overlap: Some(N),
+            ..PsdOptions::new(SAMPLE_RATE)
+        };
+        assert_eq!(
+            welch(&engine, &signal, &options).err(),
+            Some(Error::InvalidOverlap { overlap: N })
+        );
+        assert_eq!(
+            welch(&engine, &signal[..N - 1], &PsdOptions::new(SAMPLE_RATE)).err(),
+            Some(Error::LengthMismatch {
+                expected: N,
+                actual: N - 1
+            })
+        );
+    }
+}
@@ -0,0 +1,548 @@
+use core::ops::Mul;
+
+use num_traits::{Float, One, Zero};
+
+use crate::{
+    Allocator, ComplexFloat, Engine, Error, Implementation, Scalar, Scaling, WindowFunction,
+};
+
+/// How the signal is extended past its ends when frames are centered on their sample.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Padding {
+    /// Frames start at sample 0 and only cover the signal itself.
+    #[default]
+    None,
+    /// Frame `f` is centered on sample `f * hop`, the signal being extended with zeros.
+    Zero,
+    /// Frame `f` is centered on sample `f * hop`, the signal being mirrored around its
+    /// first and last samples (`x[-1] = x[1]`).
+    Reflect,
+}
+
+/// Short-time Fourier transform over frames of N samples, windowed by `W` and spaced
+/// by `hop` samples.
+pub struct Stft<T, const N: usize, I, W, A>
+where
+    A: Allocator<T, N>,
+    I: Implementation<T, N, A>,
+    W: WindowFunction<T>,
+    T: Copy + ComplexFloat,
+{
+    engine: Engine<T, N, I, W, A>,
+    hop: usize,
+    padding: Padding,
+}
+
+impl<T, const N: usize, I, W, A> Stft<T, N, I, W, A>
+where
+    A: Allocator<T, N>,
+    I: Implementation<T, N, A>,
+    W: WindowFunction<T>,
+    T: Copy + ComplexFloat + Mul<Scalar<T>, Output = T>,
+{
+    /// Returns [`Error::InvalidHop`] if `hop` is zero.
+    pub fn new(hop: usize, padding: Padding) -> Result<Stft<T, N, I, W, A>, Error>
+    where
+        W: Default,
+    {
+        Stft::with_window(W::default(), hop, padding)
+    }
+
+    /// Same as [`Stft::new`], for windows carrying parameters or coefficients.
+    pub fn with_window(
+        window: W,
+        hop: usize,
+        padding: Padding,
+    ) -> Result<Stft<T, N, I, W, A>, Error> {
+        if hop == 0 {
+            return Err(Error::InvalidHop { hop });
+        }
+
+        Ok(Stft {
+            engine: Engine::with_window(window),
+            hop,
+            padding,
+        })
+    }
+
+    pub fn engine(&self) -> &Engine<T, N, I, W, A> {
+        &self.engine
+    }
+
+    pub fn hop(&self) -> usize {
+        self.hop
+    }
+
+    pub fn padding(&self) -> Padding {
+        self.padding
+    }
+
+    /// Number of frames produced for a signal of `len` samples.
+    pub fn frame_count(&self, len: usize) -> usize {
+        let padded_len = match self.padding {
+            Padding::None => len,
+            Padding::Zero | Padding::Reflect if len > 0 => len + 2 * (N / 2),
+            _ => 0,
+        };
+
+        if padded_len < N {
+            0
+        } else {
+            1 + (padded_len - N) / self.hop
+        }
+    }
+
+    /// Iterates over the spectra of the frames of `signal`.
+    pub fn frames<'s>(&'s self, signal: &'s [T]) -> Frames<'s, T, N, I, W, A> {
+        Frames {
+            stft: self,
+            signal,
+            frame: 0,
+            count: self.frame_count(signal.len()),
+        }
+    }
+
+    /// Writes the spectra of the frames of `signal` into `output`, one row per frame,
+    /// and returns the number of frames.
+    ///
+    /// Returns [`Error::LengthMismatch`] if `output` holds fewer rows than
+    /// [`Stft::frame_count`].
+    pub fn process(&self, signal: &[T], output: &mut [A::Element]) -> Result<usize, Error> {
+        let count = self.frame_count(signal.len());
+        if output.len() < count {
+            return Err(Error::LengthMismatch {
+                expected: count,
+                actual: output.len(),
+            });
+        }
+
+        for (frame, spectrum) in output.iter_mut().take(count).enumerate() {
+            self.frame_into(signal, frame, spectrum.as_mut());
+        }
+        Ok(count)
+    }
+
+    /// Inverse of [`Stft::process`], resynthesizing `output.len()` samples from the
+    /// spectra of `frames` by weighted overlap-add.
+    ///
+    /// Each frame is windowed again after its inverse transform and the sum of the
+    /// overlapping frames is divided by the sum of the squared windows, so frames
+    /// modified in the frequency domain are crossfaded smoothly. Samples no frame
+    /// contributes to (e.g. the first sample of a [`Padding::None`] transform with a
+    /// window starting at zero) are set to zero.
+    ///
+    /// # Errors
+    /// Returns [`Error::NotNola`] if the window and hop cannot be inverted, see
+    /// [`Stft::check_nola`], and [`Error::NotCola`] if they do not satisfy the constant
+    /// overlap-add condition, see [`Stft::check_cola`]. Returns [`Error::LengthMismatch`]
+    /// if a frame does not hold N bins, `output` being left partially written.
+    pub fn inverse<F: AsRef<[T]>>(
+        &self,
+        frames: impl IntoIterator<Item = F>,
+        output: &mut [T],
+    ) -> Result<(), Error> {
+        self.check_nola()?;
+        self.check_cola()?;
+        let window = self.engine.window_table();
+        let offset = self.offset() as isize;
+        let len = output.len() as isize;
+        output.fill(T::zero());
+
+        // 1. Overlap-add the windowed inverse transforms
+        let mut buffer = A::allocate();
+        let buffer = buffer.as_mut();
+        let mut count = 0;
+        for spectrum in frames {
+            let spectrum = spectrum.as_ref();
+            if spectrum.len() != N {
+                return Err(Error::LengthMismatch {
+                    expected: N,
+                    actual: spectrum.len(),
+                });
+            }
+            buffer.copy_from_slice(spectrum);
+            self.engine.ifft_in_place(buffer, Scaling::Normalized);
+
+            let start = (count * self.hop) as isize - offset;
+            for (j, (x, w)) in buffer.iter().zip(window.iter()).enumerate() {
+                let t = start + j as isize;
+                if (0..len).contains(&t) {
+                    output[t as usize] = output[t as usize] + *x * w;
+                }
+            }
+            count += 1;
+        }
+
+        // 2. Normalize each sample by the squared window sum of the frames covering it
+        for (t, x) in output.iter_mut().enumerate() {
+            let t = t + self.offset();
+            let first = (t + 1).saturating_sub(N).div_ceil(self.hop);
+            let norm = (first..count.min(t / self.hop + 1))
+                .map(|f| window.get(t - f * self.hop))
+                .fold(Scalar::<T>::zero(), |acc, w| acc + w * w);
+
+            *x = if norm > Scalar::<T>::epsilon() {
+                *x * (Scalar::<T>::one() / norm)
+            } else {
+                T::zero()
+            };
+        }
+        Ok(())
+    }
+
+    /// Checks the constant overlap-add condition: the window shifted by every multiple
+    /// of the hop sums to a constant, so unmodified frames overlap-added without any
+    /// normalization give back the signal up to a constant gain.
+    ///
+    /// Returns [`Error::NotCola`] otherwise.
+    pub fn check_cola(&self) -> Result<(), Error> {
+        // Hops longer than the window leave gaps with no frame at all
+        if self.hop > N {
+            return Err(Error::NotCola { hop: self.hop });
+        }
+
+        let window = self.engine.window_table();
+        let sums = (0..self.hop).map(|n| {
+            window
+                .iter()
+                .skip(n)
+                .step_by(self.hop)
+                .fold(Scalar::<T>::zero(), |acc, w| acc + w)
+        });
+
+        let tolerance = Float::sqrt(Scalar::<T>::epsilon());
+        let mut reference = None;
+        for sum in sums {
+            let reference = *reference.get_or_insert(sum);
+            if Float::abs(sum - reference)
+                > tolerance * Float::abs(reference).max(Scalar::<T>::one())
+            {
+                return Err(Error::NotCola { hop: self.hop });
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks the nonzero overlap-add condition: every sample is covered by at least
+    /// one frame whose squared window is nonzero there, which [`Stft::inverse`] needs
+    /// to divide by the window sum.
+    ///
+    /// Returns [`Error::NotNola`] otherwise.
+    pub fn check_nola(&self) -> Result<(), Error> {
+        let window = self.engine.window_table();
+        let covered = (0..self.hop).all(|n| {
+            window
+                .iter()
+                .skip(n)
+                .step_by(self.hop)
+                .fold(Scalar::<T>::zero(), |acc, w| acc + w * w)
+                > Scalar::<T>::epsilon()
+        });
+
+        if covered {
+            Ok(())
+        } else {
+            Err(Error::NotNola { hop: self.hop })
+        }
+    }
+
+    fn offset(&self) -> usize {
+        match self.padding {
+            Padding::None => 0,
+            Padding::Zero | Padding::Reflect => N / 2,
+        }
+    }
+
+    fn frame_into(&self, signal: &[T], frame: usize, spectrum: &mut [T]) {
+        let start = (frame * self.hop) as isize - self.offset() as isize;
+
+        for (j, x) in spectrum.iter_mut().enumerate() {
+            *x = self.sample(signal, start + j as isize);
+        }
+        self.engine.fft_in_place(spectrum);
+    }
+
+    fn sample(&self, signal: &[T], t: isize) -> T {
+        let len = signal.len() as isize;
+        if (0..len).contains(&t) {
+            return signal[t as usize];
+        }
+
+        match self.padding {
+            Padding::Reflect if len > 1 => {
+                // Mirroring is periodic over 2 * (len - 1) samples
+                let period = 2 * (len - 1);
+                let t = t.rem_euclid(period);
+                signal[(if t < len { t } else { period - t }) as usize]
+            }
+            Padding::Reflect => signal[0],
+            _ => T::zero(),
+        }
+    }
+}
+
+/// Iterator over the spectra of the frames of a signal, see [`Stft::frames`].
+pub struct Frames<'s, T, const N: usize, I, W, A>
+where
+    A: Allocator<T, N>,
+    I: Implementation<T, N, A>,
+    W: WindowFunction<T>,
+    T: Copy + ComplexFloat,
+{
+    stft: &'s Stft<T, N, I, W, A>,
+    signal: &'s [T],
+    frame: usize,
+    count: usize,
+}
+
+impl<T, const N: usize, I, W, A> Iterator for Frames<'_, T, N, I, W, A>
+where
+    A: Allocator<T, N>,
+    I: Implementation<T, N, A>,
+    W: WindowFunction<T>,
+    T: Copy + ComplexFloat + Mul<Scalar<T>, Output = T>,
+{
+    type Item = A::Element;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.frame >= self.count {
+            return None;
+        }
+
+        let mut spectrum = A::allocate();
+        self.stft
+            .frame_into(self.signal, self.frame, spectrum.as_mut());
+        self.frame += 1;
+        Some(spectrum)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.count - self.frame;
+        (remaining, Some(remaining))
+    }
+}
+
+impl<T, const N: usize, I, W, A> ExactSizeIterator for Frames<'_, T, N, I, W, A>
+where
+    A: Allocator<T, N>,
+    I: Implementation<T, N, A>,
+    W: WindowFunction<T>,
+    T: Copy + ComplexFloat + Mul<Scalar<T>, Output = T>,
+{
+}
+
+#[cfg(test)]
+mod test {
+    use num_complex::Complex32;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    use crate::{
+        allocators::{array::ArrayAllocator, boxed::BoxedAllocator},
+        implementations::CooleyTukey,
+        test::array_assert_eq,
+        windows::{hamming::Hamming, hanning::Hanning, Rect},
+        Allocator, Error, WindowFunction,
+    };
+
+    use super::{Padding, Stft};
+
+    const N: usize = 16;
+
+    fn signal(len: usize) -> Vec<Complex32> {
+        let mut rng = StdRng::seed_from_u64(1234);
+        (0..len).map(|_| rng.gen()).collect()
+    }
+
+    #[test]
+    fn frame_count_test() {
+        let stft =
+            Stft::<Complex32, N, CooleyTukey, Rect, ArrayAllocator>::new(4, Padding::None).unwrap();
+        assert_eq!(stft.frame_count(0), 0);
+        assert_eq!(stft.frame_count(15), 0);
+        assert_eq!(stft.frame_count(16), 1);
+        assert_eq!(stft.frame_count(19), 1);
+        assert_eq!(stft.frame_count(20), 2);
+
+        let stft =
+            Stft::<Complex32, N, CooleyTukey, Rect, ArrayAllocator>::new(4, Padding::Zero).unwrap();
+        assert_eq!(stft.frame_count(0), 0);
+        assert_eq!(stft.frame_count(1), 1);
+        assert_eq!(stft.frame_count(20), 6);
+
+        assert_eq!(
+            Stft::<Complex32, N, CooleyTukey, Rect, ArrayAllocator>::new(0, Padding::None).err(),
+            Some(Error::InvalidHop { hop: 0 })
+        );
+    }
+
+    #[test]
+    fn frames_test() {
+        let stft =
+            Stft::<Complex32, N, CooleyTukey, Hanning, BoxedAllocator>::new(5, Padding::None)
+                .unwrap();
+        let signal = signal(100);
+
+        let frames = stft.frames(&signal);
+        assert_eq!(frames.len(), stft.frame_count(signal.len()));
+        for (f, spectrum) in frames.enumerate() {
+            let mut expected = <BoxedAllocator as Allocator<Complex32, N>>::allocate();
+            stft.engine().fft(&signal[f * 5..f * 5 + N], &mut expected);
+            array_assert_eq(expected.as_ref(), spectrum.as_ref(), 1e-4);
+        }
+    }
+
+    #[test]
+    fn padding_test() {
+        let signal = signal(40);
+        let zero =
+            Stft::<Complex32, N, CooleyTukey, Rect, ArrayAllocator>::new(8, Padding::Zero).unwrap();
+        let reflect =
+            Stft::<Complex32, N, CooleyTukey, Rect, ArrayAllocator>::new(8, Padding::Reflect)
+                .unwrap();
+
+        // First frame is centered on sample 0
+        let mut padded = [Complex32::default(); N];
+        padded[N / 2..].copy_from_slice(&signal[..N / 2]);
+        let mut expected = [Complex32::default(); N];
+        zero.engine().fft(&padded, &mut expected);
+        let first = zero.frames(&signal).next().unwrap();
+        array_assert_eq(&expected, &first, 1e-4);
+
+        for j in 0..N / 2 {
+            padded[j] = signal[N / 2 - j];
+        }
+        reflect.engine().fft(&padded, &mut expected);
+        let first = reflect.frames(&signal).next().unwrap();
+        array_assert_eq(&expected, &first, 1e-4);
+
+        // Last frame is centered on sample 40, mirrored around sample 39
+        let last = reflect.frames(&signal).last().unwrap();
+        for (j, x) in padded.iter_mut().enumerate() {
+            let t = 40 - N / 2 + j;
+            *x = signal[if t < 40 { t } else { 78 - t }];
+        }
+        reflect.engine().fft(&padded, &mut expected);
+        array_assert_eq(&expected, &last, 1e-4);
+    }
+
+    #[test]
+    fn process_test() {
+        let stft =
+            Stft::<Complex32, N, CooleyTukey, Hanning, ArrayAllocator>::new(4, Padding::Reflect)
+                .unwrap();
+        let signal = signal(64);
+        let count = stft.frame_count(signal.len());
+
+        let mut output = vec![[Complex32::default(); N]; count];
+        assert_eq!(stft.process(&signal, &mut output), Ok(count));
+        for (expected, spectrum) in stft.frames(&signal).zip(output.iter()) {
+            array_assert_eq(&expected, spectrum, 1e-6);
+        }
+
+        assert_eq!(
+            stft.process(&signal, &mut output[1..]),
+            Err(Error::LengthMismatch {
+                expected: count,
+                actual: count - 1
+            })
+        );
+    }
+
+    fn round_trip_test<W: WindowFunction<Complex32> + Default>(hop: usize, padding: Padding) {
+        let stft = Stft::<Complex32, N, CooleyTukey, W, ArrayAllocator>::new(hop, padding).unwrap();
+        let signal = signal(100);
+
+        let mut output = vec![Complex32::default(); signal.len()];
+        stft.inverse(stft.frames(&signal), &mut output).unwrap();
+        array_assert_eq(signal.as_slice(), output.as_slice(), 1e-4);
+
+        // Spectra stored in a caller buffer work the same way
+        let mut spectra = vec![[Complex32::default(); N]; stft.frame_count(signal.len())];
+        stft.process(&signal, &mut spectra).unwrap();
+        output.fill(Complex32::default());
+        stft.inverse(&spectra, &mut output).unwrap();
+        array_assert_eq(signal.as_slice(), output.as_slice(), 1e-4);
+    }
+
+    #[test]
+    fn inverse_round_trip_test() {
+        round_trip_test::<Hanning>(N / 2, Padding::Zero);
+        round_trip_test::<Hanning>(N / 4, Padding::Reflect);
+        round_trip_test::<Hanning>(N / 8, Padding::Zero);
+        round_trip_test::<Hamming>(N / 2, Padding::Reflect);
+        round_trip_test::<Rect>(N, Padding::Zero);
+    }
+
+    #[test]
+    fn inverse_unpadded_test() {
+        let stft =
+            Stft::<Complex32, N, CooleyTukey, Hanning, ArrayAllocator>::new(N / 4, Padding::None)
+                .unwrap();
+        let signal = signal(100);
+
+        let mut output = vec![Complex32::default(); signal.len()];
+        stft.inverse(stft.frames(&signal), &mut output).unwrap();
+
+        // The first sample only sees the zero at the start of the first window, and the
+        // tail past the last frame is not covered at all
+        let covered = (stft.frame_count(signal.len()) - 1) * N / 4 + N;
+        assert_eq!(output[0], Complex32::default());
+        array_assert_eq(&signal[1..covered], &output[1..covered], 1e-4);
+        assert!(output[covered..].iter().all(|x| *x == Complex32::default()));
+    }
+
+    #[test]
+    fn overlap_add_condition_test() {
+        let hanning = |hop| {
+            Stft::<Complex32, N, CooleyTukey, Hanning, ArrayAllocator>::new(hop, Padding::Zero)
+                .unwrap()
+        };
+        assert_eq!(hanning(N / 2).check_cola(), Ok(()));
+        assert_eq!(hanning(N / 4).check_cola(), Ok(()));
+        assert_eq!(hanning(3).check_cola(), Err(Error::NotCola { hop: 3 }));
+        assert_eq!(hanning(3).check_nola(), Ok(()));
+        let signal = signal(64);
+        let mut output = vec![Complex32::default(); signal.len()];
+        assert_eq!(
+            hanning(3).inverse(hanning(3).frames(&signal), &mut output),
+            Err(Error::NotCola { hop: 3 })
+        );
+        assert_eq!(hanning(N).check_cola(), Err(Error::NotCola { hop: N }));
+
+        // Without overlap the zero at the start of each Hanning window is never covered
+        let stft = hanning(N);
+        assert_eq!(stft.check_nola(), Err(Error::NotNola { hop: N }));
+        assert_eq!(
+            stft.inverse(stft.frames(&signal), &mut output),
+            Err(Error::NotNola { hop: N })
+        );
+
+        let rect =
+            Stft::<Complex32, N, CooleyTukey, Rect, ArrayAllocator>::new(N, Padding::None).unwrap();
+        assert_eq!(rect.check_cola(), Ok(()));
+        assert_eq!(rect.check_nola(), Ok(()));
+        let hamming =
+            Stft::<Complex32, N, CooleyTukey, Hamming, ArrayAllocator>::new(N / 2, Padding::None)
+                .unwrap();
+        assert_eq!(hamming.check_cola(), Ok(()));
+    }
+
+    #[test]
+    fn inverse_frame_length_test() {
+        let stft =
+            Stft::<Complex32, N, CooleyTukey, Hanning, ArrayAllocator>::new(N / 2, Padding::Zero)
+                .unwrap();
+        let short = vec![
+            vec![Complex32::default(); N],
+            vec![Complex32::default(); N - 1],
+        ];
+        let mut output = vec![Complex32::default(); 32];
+        assert_eq!(
+            stft.inverse(&short, &mut output),
+            Err(Error::LengthMismatch {
+                expected: N,
+                actual: N - 1
+            })
+        );
+    }
+}
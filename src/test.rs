@@ -9,18 +9,21 @@ use core::{
 };
 
 use approx::{assert_relative_eq, AbsDiffEq, RelativeEq};
-use num_complex::{Complex32, ComplexFloat};
+use num_complex::{Complex32, Complex64, ComplexFloat};
 use num_traits::{One, Zero};
 
 use rand::{distributions::Standard, prelude::Distribution, rngs::StdRng, Rng, SeedableRng};
 use std::sync::Arc;
 
 use crate::{
-    allocators::boxed::BoxedAllocator, implementations::Naive, windows::Rect, Allocator, Engine,
-    ImgUnit, Implementation, Scalar, WindowFunction,
+    allocators::boxed::BoxedAllocator,
+    implementations::Naive,
+    scalar,
+    windows::{hanning::Hanning, Rect},
+    Allocator, Engine, ImgUnit, Implementation, Scalar, Scaling, WindowFunction,
 };
-const ALPHA: Scalar = 0.5;
-const BETA: Scalar = 0.75;
+const ALPHA: f64 = 0.5;
+const BETA: f64 = 0.75;
 const N: usize = 32;
 
 pub(crate) trait EngineTest<T, const N: usize, A, W, I>
@@ -28,7 +31,7 @@ where
     A: Allocator<T, N>,
     I: Implementation<T, N, A>,
     W: WindowFunction<T>,
-    T: Copy + Mul<Scalar, Output = T> + ImgUnit + ComplexFloat,
+    T: Copy + Mul<Scalar<T>, Output = T> + ImgUnit + ComplexFloat,
 {
     fn naive_engine() -> Engine<T, N, Naive, W, A>;
     fn test_engine() -> Engine<T, N, I, Rect, A>;
@@ -49,7 +52,12 @@ pub(crate) struct TestFixture<
 impl<T, const N: usize, A: Allocator<T, N>, I: Implementation<T, N, A>> EngineTest<T, N, A, Rect, I>
     for TestFixture<T, N, A, I>
 where
-    T: Copy + Add<Output = T> + Sub<Output = T> + Mul<Scalar, Output = T> + ImgUnit + ComplexFloat,
+    T: Copy
+        + Add<Output = T>
+        + Sub<Output = T>
+        + Mul<Scalar<T>, Output = T>
+        + ImgUnit
+        + ComplexFloat,
 {
     fn naive_engine() -> Engine<T, N, Naive, Rect, A> {
         Engine::new()
@@ -71,8 +79,8 @@ where
         + AddAssign<T>
         + Sub<Output = T>
         + MulAssign<T>
-        + MulAssign<Scalar>
-        + Mul<Scalar, Output = T>
+        + MulAssign<Scalar<T>>
+        + Mul<Scalar<T>, Output = T>
         + ImgUnit
         + ComplexFloat
         + Default
@@ -80,7 +88,7 @@ where
         + One
         + Zero
         + RelativeEq,
-    T::Epsilon: Copy + From<Scalar>,
+    T::Epsilon: Copy + From<Scalar<T>>,
     Standard: Distribution<T>,
 {
     pub fn impulse_test() {
@@ -91,18 +99,18 @@ where
         array_assert_eq(
             generate::<N, T>(|_| T::one()).as_slice(),
             fft_impulse.as_ref(),
-            T::Epsilon::from(1e-1),
+            T::Epsilon::from(scalar(1e-1)),
         );
     }
 
     pub fn linearity_test() {
         let engine = Self::test_engine();
-        let v = generate::<N, T>(|idx| T::one() * idx as Scalar);
-        let e = generate::<N, T>(|idx| T::one() * (idx + 1usize) as Scalar);
+        let v = generate::<N, T>(|idx| T::one() * scalar::<Scalar<T>>(idx));
+        let e = generate::<N, T>(|idx| T::one() * scalar::<Scalar<T>>(idx + 1));
 
         // FFT of sum
-        let mut a_v = v.map(|v| v * ALPHA);
-        let b_e = e.map(|v| v * BETA);
+        let mut a_v = v.map(|v| v * scalar::<Scalar<T>>(ALPHA));
+        let b_e = e.map(|v| v * scalar::<Scalar<T>>(BETA));
         sum_v(&mut a_v, b_e.into_iter());
         let sum = a_v;
         let mut fft_sum = A::allocate();
@@ -111,16 +119,23 @@ where
         //Sum of FFT
         let mut a_fft_v = A::allocate();
         engine.fft(v.as_slice(), &mut a_fft_v);
-        mul_v(a_fft_v.as_mut(), ALPHA);
+        mul_v(a_fft_v.as_mut(), scalar::<Scalar<T>>(ALPHA));
         let mut e_fft_v = A::allocate();
         engine.fft(e.as_slice(), &mut e_fft_v);
         sum_v(
             a_fft_v.as_mut(),
-            e_fft_v.as_mut().iter_mut().map(|x| *x * BETA),
+            e_fft_v
+                .as_mut()
+                .iter_mut()
+                .map(|x| *x * scalar::<Scalar<T>>(BETA)),
         );
         let sum_fft = a_fft_v;
 
-        array_assert_eq(fft_sum.as_ref(), sum_fft.as_ref(), T::Epsilon::from(1e-1));
+        array_assert_eq(
+            fft_sum.as_ref(),
+            sum_fft.as_ref(),
+            T::Epsilon::from(scalar(1e-1)),
+        );
     }
 
     const GROUND_TEST_SEEDS: [u64; 3] = [1234, 495611, 38596722];
@@ -138,12 +153,112 @@ where
             let mut naive_fft_v = A::allocate();
             naive_engine.fft(v.as_slice(), &mut naive_fft_v);
             println!("naive_fft: {:?}", naive_fft_v.as_ref());
-            array_assert_eq(naive_fft_v.as_ref(), fft_v.as_ref(), T::Epsilon::from(1e-1));
+            array_assert_eq(
+                naive_fft_v.as_ref(),
+                fft_v.as_ref(),
+                T::Epsilon::from(scalar(1e-1)),
+            );
+        }
+    }
+
+    pub fn round_trip_test() {
+        let engine = Self::test_engine();
+
+        for seed in Self::GROUND_TEST_SEEDS.iter() {
+            let mut rng = StdRng::seed_from_u64(*seed);
+            let v = (0..N).map(|_| rng.gen()).collect::<Vec<_>>();
+            let mut fft_v = Self::allocate();
+            engine.fft(v.as_slice(), &mut fft_v);
+
+            let mut round_trip = Self::allocate();
+            engine.ifft(fft_v.as_ref(), &mut round_trip, Scaling::Normalized);
+            array_assert_eq(
+                v.as_slice(),
+                round_trip.as_ref(),
+                T::Epsilon::from(scalar(1e-3)),
+            );
+
+            // Without scaling the round trip is N times the original signal
+            engine.ifft(fft_v.as_ref(), &mut round_trip, Scaling::Unnormalized);
+            mul_v(round_trip.as_mut(), Scalar::<T>::one() / scalar(N));
+            array_assert_eq(
+                v.as_slice(),
+                round_trip.as_ref(),
+                T::Epsilon::from(scalar(1e-3)),
+            );
+        }
+    }
+
+    pub fn in_place_test() {
+        let engine = Self::test_engine();
+        let hanning_engine = Engine::<T, N, I, Hanning, A>::new();
+
+        for seed in Self::GROUND_TEST_SEEDS.iter() {
+            let mut rng = StdRng::seed_from_u64(*seed);
+            let v = (0..N).map(|_| rng.gen()).collect::<Vec<_>>();
+            let mut scratch = Self::allocate();
+
+            let mut fft_v = Self::allocate();
+            engine.fft(v.as_slice(), &mut fft_v);
+            let mut in_place = v.clone();
+            engine.fft_in_place(&mut in_place);
+            array_assert_eq(
+                fft_v.as_ref(),
+                in_place.as_slice(),
+                T::Epsilon::from(scalar(1e-3)),
+            );
+
+            engine.ifft_in_place_with_scratch(&mut in_place, scratch.as_mut(), Scaling::Normalized);
+            array_assert_eq(
+                v.as_slice(),
+                in_place.as_slice(),
+                T::Epsilon::from(scalar(1e-3)),
+            );
+
+            hanning_engine.fft(v.as_slice(), &mut fft_v);
+            let mut in_place = v.clone();
+            hanning_engine.fft_in_place_with_scratch(&mut in_place, scratch.as_mut());
+            array_assert_eq(
+                fft_v.as_ref(),
+                in_place.as_slice(),
+                T::Epsilon::from(scalar(1e-3)),
+            );
+
+            let mut ifft_v = Self::allocate();
+            engine.ifft(fft_v.as_ref(), &mut ifft_v, Scaling::Unnormalized);
+            engine.ifft_in_place(&mut in_place, Scaling::Unnormalized);
+            array_assert_eq(
+                ifft_v.as_ref(),
+                in_place.as_slice(),
+                T::Epsilon::from(scalar(1e-3)),
+            );
+        }
+    }
+
+    pub fn inverse_ground_truth_test() {
+        let engine = Self::test_engine();
+        let naive_engine = Self::naive_engine();
+
+        for seed in Self::GROUND_TEST_SEEDS.iter() {
+            let mut rng = StdRng::seed_from_u64(*seed);
+            let v = (0..N).map(|_| rng.gen()).collect::<Vec<_>>();
+            let mut ifft_v = Self::allocate();
+            engine.ifft(v.as_slice(), &mut ifft_v, Scaling::Unnormalized);
+            let mut naive_ifft_v = Self::allocate();
+            naive_engine.ifft(v.as_slice(), &mut naive_ifft_v, Scaling::Unnormalized);
+            array_assert_eq(
+                naive_ifft_v.as_ref(),
+                ifft_v.as_ref(),
+                T::Epsilon::from(scalar(1e-1)),
+            );
         }
     }
 }
 
 pub(crate) type ComplexTestFixture<I> = TestFixture<Complex32, N, BoxedAllocator, I>;
+pub(crate) type DoubleComplexTestFixture<I> = TestFixture<Complex64, N, BoxedAllocator, I>;
+pub(crate) type SizedComplexTestFixture<I, const S: usize> =
+    TestFixture<Complex32, S, BoxedAllocator, I>;
 
 pub(crate) fn sum_v<T>(a: &mut [T], b: impl Iterator<Item = T>)
 where
@@ -1,22 +1,360 @@
+#[cfg(feature = "alloc")]
+use alloc::boxed::Box;
+
 use core::{iter::Copied, ops::Mul};
 
+use num_complex::ComplexFloat;
+use num_traits::Float;
+
 use crate::{Scalar, WindowFunction};
 
+pub mod bartlett;
+pub mod bartlett_hann;
+pub mod blackman;
+pub mod blackman_harris;
+pub mod blackman_nuttall;
+#[cfg(feature = "alloc")]
+pub mod chebyshev;
+pub mod cosine;
+pub mod cosine_sum;
+pub mod custom;
+#[cfg(feature = "alloc")]
+pub mod dpss;
+pub mod flat_top;
+pub mod gaussian;
 pub mod hamming;
 pub mod hanning;
+pub mod kaiser;
+pub mod metrics;
+pub mod nuttall;
+pub mod tukey;
+pub mod welch;
 
+/// How a window of `len` samples is sampled from its continuous shape.
+pub trait Symmetry {
+    /// Period `n` the window is sampled over, coefficient `i` being taken at `i / n`.
+    fn period(len: usize) -> usize;
+}
+
+/// DFT-even form, sampled over `len` points, for spectral analysis: the window is one
+/// period of a periodic sequence, so `w[i] == w[len - i]` and `w[len - 1]` has no mirror.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Periodic;
+impl Symmetry for Periodic {
+    fn period(len: usize) -> usize {
+        len
+    }
+}
+
+/// Symmetric form, sampled over `len - 1` points, for FIR filter design:
+/// `w[i] == w[len - 1 - i]`.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Symmetric;
+impl Symmetry for Symmetric {
+    fn period(len: usize) -> usize {
+        len.saturating_sub(1).max(1)
+    }
+}
+
+/// Rectangular window, identical in its periodic and symmetric forms.
+#[derive(Clone, Copy, Debug, Default)]
 pub struct Rect;
 impl<T> WindowFunction<T> for Rect
 where
-    T: Copy + Mul<Scalar, Output = T>,
+    T: Copy + ComplexFloat + Mul<Scalar<T>, Output = T>,
 {
-    type ItemMapper<'a, TIter: IntoIterator<Item = &'a T>> = Copied<TIter::IntoIter> where T : 'a;
-    fn windowed<'a, const N: usize, TIter: IntoIterator<Item = &'a T>>(
+    type ItemMapper<'w, 'a, TIter: IntoIterator<Item = &'a T>> = Copied<TIter::IntoIter> where T : 'a, Self: 'w;
+    fn windowed<'w, 'a, const N: usize, TIter: IntoIterator<Item = &'a T>>(
+        &'w self,
+        v: TIter,
+    ) -> Self::ItemMapper<'w, 'a, TIter>
+    where
+        T: 'a,
+    {
+        v.into_iter().copied()
+    }
+
+    type DynItemMapper<'w, 'a, TIter: IntoIterator<Item = &'a T>> = Copied<TIter::IntoIter> where T : 'a, Self: 'w;
+    fn windowed_dyn<'w, 'a, TIter: IntoIterator<Item = &'a T>>(
+        &'w self,
         v: TIter,
-    ) -> Self::ItemMapper<'a, TIter>
+        _len: usize,
+    ) -> Self::DynItemMapper<'w, 'a, TIter>
     where
         T: 'a,
     {
         v.into_iter().copied()
     }
+
+    const IDENTITY: bool = true;
+
+    fn window_in_place(&self, _buffer: &mut [T]) {}
+}
+
+/// Coefficients of a window of N samples, computed once when an [`crate::Engine`] is built
+/// so that frames are windowed with one multiplication per sample.
+///
+/// Nothing is stored for windows that leave samples untouched ([`WindowFunction::IDENTITY`]),
+/// whose coefficients all read as one.
+pub struct WindowTable<R, const N: usize> {
+    #[cfg(feature = "alloc")]
+    coefficients: Option<Box<[R]>>,
+    #[cfg(not(feature = "alloc"))]
+    coefficients: Option<[R; N]>,
+}
+
+impl<R: Float, const N: usize> WindowTable<R, N> {
+    pub fn new<T, W>(window: &W) -> WindowTable<R, N>
+    where
+        W: WindowFunction<T>,
+        T: ComplexFloat<Real = R>,
+    {
+        if W::IDENTITY {
+            return WindowTable { coefficients: None };
+        }
+
+        let one = T::one();
+        let coefficients = window
+            .windowed::<N, _>(core::iter::repeat_n(&one, N))
+            .into_iter()
+            .map(|w| w.re());
+
+        #[cfg(feature = "alloc")]
+        let coefficients = coefficients.collect();
+        #[cfg(not(feature = "alloc"))]
+        let coefficients = {
+            let mut coefficients = coefficients;
+            core::array::from_fn(|_| coefficients.next().unwrap_or(R::one()))
+        };
+
+        WindowTable {
+            coefficients: Some(coefficients),
+        }
+    }
+
+    /// The stored coefficients, `None` for identity windows.
+    pub fn coefficients(&self) -> Option<&[R]> {
+        self.coefficients
+            .as_ref()
+            .map(|coefficients| &coefficients[..])
+    }
+
+    /// Coefficient `i`, one for identity windows.
+    ///
+    /// # Panics
+    /// Panics if `i` is not below N.
+    pub fn get(&self, i: usize) -> R {
+        assert!(i < N, "coefficient {i} out of a window of {N}");
+        self.coefficients()
+            .map_or(R::one(), |coefficients| coefficients[i])
+    }
+
+    /// The N coefficients, ones for identity windows.
+    pub fn iter(&self) -> impl Iterator<Item = R> + Clone + '_ {
+        let (stored, ones): (&[R], usize) = match self.coefficients() {
+            Some(coefficients) => (coefficients, 0),
+            None => (&[], N),
+        };
+        stored
+            .iter()
+            .copied()
+            .chain(core::iter::repeat_n(R::one(), ones))
+    }
+
+    /// Windows `buffer` in place.
+    pub(crate) fn apply<T: Copy + Mul<R, Output = T>>(&self, buffer: &mut [T]) {
+        if let Some(coefficients) = self.coefficients() {
+            for (x, w) in buffer.iter_mut().zip(coefficients.iter()) {
+                *x = *x * *w;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use approx::assert_relative_eq;
+    use num_complex::Complex64;
+
+    use crate::{
+        allocators::array::ArrayAllocator, implementations::CooleyTukey, Engine, WindowFunction,
+    };
+
+    use super::{
+        bartlett::Bartlett,
+        bartlett_hann::BartlettHann,
+        blackman::Blackman,
+        blackman_harris::BlackmanHarris,
+        blackman_nuttall::BlackmanNuttall,
+        cosine::Cosine,
+        flat_top::FlatTop,
+        gaussian::Gaussian,
+        hamming::Hamming,
+        hanning::Hanning,
+        kaiser::{Beta, DynKaiser, Kaiser},
+        nuttall::Nuttall,
+        tukey::Tukey,
+        welch::Welch,
+        Periodic, Rect, Symmetric,
+    };
+
+    fn coefficients<W: WindowFunction<f64>>(window: &W, len: usize) -> Vec<f64> {
+        let mut coefficients = vec![1.0; len];
+        window.window_in_place(&mut coefficients);
+        coefficients
+    }
+
+    fn symmetry_test<P, S>(periodic: P, symmetric: S)
+    where
+        P: WindowFunction<f64>,
+        S: WindowFunction<f64>,
+    {
+        for len in [15, 16] {
+            let p = coefficients(&periodic, len);
+            let s = coefficients(&symmetric, len);
+
+            // Symmetric: both endpoints match and so does every pair around the center
+            for i in 0..len {
+                assert_relative_eq!(s[i], s[len - 1 - i], epsilon = 1e-12);
+            }
+
+            // Periodic: symmetric around len / 2, the last sample being left without mirror
+            for i in 1..len {
+                assert_relative_eq!(p[i], p[len - i], epsilon = 1e-12);
+            }
+            assert!((p[0] - p[len - 1]).abs() > 1e-6);
+
+            // A symmetric window is the periodic window one sample shorter, plus its mirror
+            let shorter = coefficients(&periodic, len - 1);
+            for i in 0..len - 1 {
+                assert_relative_eq!(s[i], shorter[i], epsilon = 1e-12);
+            }
+            assert_relative_eq!(s[len - 1], shorter[0], epsilon = 1e-12);
+        }
+    }
+
+    #[test]
+    fn endpoint_symmetry_test() {
+        symmetry_test(<Hanning>::default(), Hanning::<Symmetric>::default());
+        symmetry_test(<Hamming>::default(), Hamming::<Symmetric>::default());
+        symmetry_test(<Blackman>::default(), Blackman::<Symmetric>::default());
+        symmetry_test(
+            <BlackmanHarris>::default(),
+            BlackmanHarris::<Symmetric>::default(),
+        );
+        symmetry_test(
+            <BlackmanNuttall>::default(),
+            BlackmanNuttall::<Symmetric>::default(),
+        );
+        symmetry_test(<Nuttall>::default(), Nuttall::<Symmetric>::default());
+        symmetry_test(<FlatTop>::default(), FlatTop::<Symmetric>::default());
+        symmetry_test(
+            Kaiser::<Beta<86>, Periodic>::default(),
+            Kaiser::<Beta<86>, Symmetric>::default(),
+        );
+        symmetry_test(
+            DynKaiser::<Periodic>::new(5.0),
+            DynKaiser::<Symmetric>::new(5.0),
+        );
+        symmetry_test(<Bartlett>::default(), Bartlett::<Symmetric>::default());
+        symmetry_test(<Welch>::default(), Welch::<Symmetric>::default());
+        symmetry_test(<Cosine>::default(), Cosine::<Symmetric>::default());
+        symmetry_test(
+            <BartlettHann>::default(),
+            BartlettHann::<Symmetric>::default(),
+        );
+        symmetry_test(<Tukey>::new(0.3), Tukey::<Symmetric>::new(0.3));
+        symmetry_test(<Gaussian>::new(0.5), Gaussian::<Symmetric>::new(0.5));
+
+        #[cfg(feature = "alloc")]
+        {
+            use super::{chebyshev::Chebyshev, dpss::Dpss};
+            symmetry_test(<Chebyshev>::new(80.0), Chebyshev::<Symmetric>::new(80.0));
+            symmetry_test(<Dpss>::new(3.0, 0), Dpss::<Symmetric>::new(3.0, 0));
+        }
+    }
+
+    #[test]
+    fn reference_values_test() {
+        // Symmetric windows of 7 samples, from scipy.signal.windows (cosine being the
+        // zero-ended sin(πi/n) form)
+        let half = |window: Vec<f64>| window[..4].to_vec();
+        let cases = [
+            (
+                half(coefficients(&Bartlett::<Symmetric>::default(), 7)),
+                [0.0, 1.0 / 3.0, 2.0 / 3.0, 1.0],
+            ),
+            (
+                half(coefficients(&Welch::<Symmetric>::default(), 7)),
+                [0.0, 5.0 / 9.0, 8.0 / 9.0, 1.0],
+            ),
+            (
+                half(coefficients(&Cosine::<Symmetric>::default(), 7)),
+                [0.0, 0.5, 0.8660254037844386, 1.0],
+            ),
+            (
+                half(coefficients(&BartlettHann::<Symmetric>::default(), 7)),
+                [0.0, 0.27, 0.73, 1.0],
+            ),
+        ];
+        for (window, expected) in cases {
+            for (w, e) in window.iter().zip(expected.iter()) {
+                assert_relative_eq!(*w, *e, epsilon = 1e-12);
+            }
+        }
+
+        // The cosine window squared is the Hanning window
+        let cosine = coefficients(&<Cosine>::default(), 16);
+        let hanning = coefficients(&<Hanning>::default(), 16);
+        for (c, h) in cosine.iter().zip(hanning.iter()) {
+            assert_relative_eq!(c * c, *h, epsilon = 1e-12);
+        }
+    }
+
+    #[test]
+    fn symmetric_hanning_test() {
+        // Symmetric Hanning starts and ends at zero, with its peak on the center sample
+        let window = coefficients(&Hanning::<Symmetric>::default(), 5);
+        for (w, expected) in window.iter().zip([0.0, 0.5, 1.0, 0.5, 0.0]) {
+            assert_relative_eq!(*w, expected, epsilon = 1e-12);
+        }
+    }
+
+    #[test]
+    fn windowed_length_test() {
+        // Shorter inputs are zero-padded after windowing
+        let engine = Engine::<Complex64, 8, CooleyTukey, Hanning, ArrayAllocator>::new();
+        let mut spectrum = [Complex64::default(); 8];
+        engine.fft(&[Complex64::new(1.0, 0.0); 7], &mut spectrum);
+        let dc = coefficients(&<Hanning>::default(), 8)[..7]
+            .iter()
+            .sum::<f64>();
+        assert_relative_eq!(spectrum[0].re, dc, epsilon = 1e-12);
+    }
+
+    #[test]
+    #[should_panic(expected = "expected at most N = 8 samples")]
+    fn windowed_overlong_test() {
+        // The window does not silently truncate the input
+        let engine = Engine::<Complex64, 8, CooleyTukey, Hanning, ArrayAllocator>::new();
+        let mut spectrum = [Complex64::default(); 8];
+        engine.fft(&[Complex64::default(); 9], &mut spectrum);
+    }
+
+    #[test]
+    fn identity_table_test() {
+        // Nothing is stored for Rect, whose coefficients still read as ones
+        let engine = Engine::<Complex64, 8, CooleyTukey, Rect, ArrayAllocator>::new();
+        let table = engine.window_table();
+        assert_eq!(table.coefficients(), None);
+        assert_eq!(table.iter().collect::<Vec<_>>(), vec![1.0; 8]);
+        assert_eq!(table.get(7), 1.0);
+
+        let engine = Engine::<Complex64, 8, CooleyTukey, Hanning, ArrayAllocator>::new();
+        let table = engine.window_table();
+        let hanning = coefficients(&<Hanning>::default(), 8);
+        assert_eq!(table.coefficients(), Some(hanning.as_slice()));
+        assert_eq!(table.iter().collect::<Vec<_>>(), hanning);
+        assert_eq!(table.get(3), hanning[3]);
+    }
 }
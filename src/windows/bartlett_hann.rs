@@ -0,0 +1,65 @@
+use core::{
+    iter::{Enumerate, Map, Repeat, Zip},
+    marker::PhantomData,
+    ops::Mul,
+};
+
+use num_complex::ComplexFloat;
+use num_traits::{Float, FloatConst};
+
+use crate::{scalar, Scalar, WindowFunction};
+
+use super::{Periodic, Symmetry};
+
+/// Bartlett-Hann window `0.62 - 0.48|i/n - 1/2| + 0.38 cos(2π(i/n - 1/2))`.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct BartlettHann<S: Symmetry = Periodic>(PhantomData<S>);
+impl<T, S> WindowFunction<T> for BartlettHann<S>
+where
+    S: Symmetry,
+    T: Copy + ComplexFloat + Mul<Scalar<T>, Output = T>,
+{
+    type ItemMapper<'w, 'a, TIter: IntoIterator<Item = &'a T>>
+        = Map<Enumerate<TIter::IntoIter>, fn((usize, &'a T)) -> T>
+    where
+        T: 'a,
+        Self: 'w;
+    fn windowed<'w, 'a, const N: usize, TIter: IntoIterator<Item = &'a T>>(
+        &'w self,
+        v: TIter,
+    ) -> Self::ItemMapper<'w, 'a, TIter> {
+        v.into_iter()
+            .enumerate()
+            .map(|(i, x)| *x * bartlett_hann::<Scalar<T>>(i, S::period(N)))
+    }
+
+    type DynItemMapper<'w, 'a, TIter: IntoIterator<Item = &'a T>>
+        = Map<Zip<Enumerate<TIter::IntoIter>, Repeat<usize>>, fn(((usize, &'a T), usize)) -> T>
+    where
+        T: 'a,
+        Self: 'w;
+    fn windowed_dyn<'w, 'a, TIter: IntoIterator<Item = &'a T>>(
+        &'w self,
+        v: TIter,
+        len: usize,
+    ) -> Self::DynItemMapper<'w, 'a, TIter> {
+        v.into_iter()
+            .enumerate()
+            .zip(core::iter::repeat(len))
+            .map(|((i, x), len)| *x * bartlett_hann::<Scalar<T>>(i, S::period(len)))
+    }
+
+    fn window_in_place(&self, buffer: &mut [T]) {
+        let n = S::period(buffer.len());
+        for (i, x) in buffer.iter_mut().enumerate() {
+            *x = *x * bartlett_hann::<Scalar<T>>(i, n);
+        }
+    }
+}
+
+#[inline(always)]
+fn bartlett_hann<R: Float + FloatConst>(i: usize, n: usize) -> R {
+    let offset = scalar::<R>(i) / scalar(n) - scalar(0.5);
+    scalar::<R>(0.62) - scalar::<R>(0.48) * offset.abs()
+        + scalar::<R>(0.38) * (scalar::<R>(2) * R::PI() * offset).cos()
+}
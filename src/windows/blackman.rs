@@ -0,0 +1,11 @@
+use core::marker::PhantomData;
+
+use super::{cosine_sum::CosineSum, Periodic, Symmetry};
+
+/// Classic 3-term Blackman window (-58 dB sidelobes).
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Blackman<S: Symmetry = Periodic>(PhantomData<S>);
+impl<S: Symmetry> CosineSum for Blackman<S> {
+    type Symmetry = S;
+    const COEFFICIENTS: &'static [f64] = &[0.42, 0.5, 0.08];
+}
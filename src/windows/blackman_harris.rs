@@ -0,0 +1,11 @@
+use core::marker::PhantomData;
+
+use super::{cosine_sum::CosineSum, Periodic, Symmetry};
+
+/// 4-term Blackman-Harris window (-92 dB sidelobes).
+#[derive(Clone, Copy, Debug, Default)]
+pub struct BlackmanHarris<S: Symmetry = Periodic>(PhantomData<S>);
+impl<S: Symmetry> CosineSum for BlackmanHarris<S> {
+    type Symmetry = S;
+    const COEFFICIENTS: &'static [f64] = &[0.35875, 0.48829, 0.14128, 0.01168];
+}
@@ -0,0 +1,11 @@
+use core::marker::PhantomData;
+
+use super::{cosine_sum::CosineSum, Periodic, Symmetry};
+
+/// 4-term Blackman-Nuttall window (-98 dB sidelobes).
+#[derive(Clone, Copy, Debug, Default)]
+pub struct BlackmanNuttall<S: Symmetry = Periodic>(PhantomData<S>);
+impl<S: Symmetry> CosineSum for BlackmanNuttall<S> {
+    type Symmetry = S;
+    const COEFFICIENTS: &'static [f64] = &[0.3635819, 0.4891775, 0.1365995, 0.0106411];
+}
@@ -0,0 +1,204 @@
+use core::{
+    iter::{Map, Zip},
+    marker::PhantomData,
+    ops::Mul,
+};
+
+use alloc::vec::{self, Vec};
+use num_complex::{Complex64, ComplexFloat};
+use num_traits::{Float, FloatConst};
+
+use crate::{implementations::MixedRadix, scalar, DynEngine, Scalar, Scaling, WindowFunction};
+
+use super::{Periodic, Rect, Symmetry};
+
+/// Dolph-Chebyshev window, whose sidelobes all sit `attenuation_db` dB below the main lobe:
+/// the narrowest main lobe for that sidelobe level.
+///
+/// Coefficients are computed for each application through the inverse transform of the
+/// window's Chebyshev polynomial spectrum, so prefer engines, which compute them once.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Chebyshev<S: Symmetry = Periodic> {
+    attenuation_db: f64,
+    symmetry: PhantomData<S>,
+}
+
+impl<S: Symmetry> Chebyshev<S> {
+    /// Chebyshev window with sidelobes `attenuation_db` dB (e.g. 100.0) below the main lobe.
+    pub fn new(attenuation_db: f64) -> Chebyshev<S> {
+        Chebyshev {
+            attenuation_db,
+            symmetry: PhantomData,
+        }
+    }
+
+    pub fn attenuation_db(&self) -> f64 {
+        self.attenuation_db
+    }
+
+    fn coefficients<R: Float>(&self, len: usize) -> Vec<R> {
+        // A periodic window is the symmetric window one sample longer, without its last sample
+        let mut coefficients = chebyshev_coefficients(self.attenuation_db, S::period(len) + 1);
+        coefficients.truncate(len);
+        coefficients.into_iter().map(scalar).collect()
+    }
+}
+
+impl<T, S> WindowFunction<T> for Chebyshev<S>
+where
+    S: Symmetry,
+    T: Copy + ComplexFloat + Mul<Scalar<T>, Output = T>,
+{
+    type ItemMapper<'w, 'a, TIter: IntoIterator<Item = &'a T>>
+        = Map<Zip<TIter::IntoIter, vec::IntoIter<Scalar<T>>>, fn((&'a T, Scalar<T>)) -> T>
+    where
+        T: 'a,
+        Self: 'w;
+    fn windowed<'w, 'a, const N: usize, TIter: IntoIterator<Item = &'a T>>(
+        &'w self,
+        v: TIter,
+    ) -> Self::ItemMapper<'w, 'a, TIter> {
+        v.into_iter().zip(self.coefficients(N)).map(|(x, w)| *x * w)
+    }
+
+    type DynItemMapper<'w, 'a, TIter: IntoIterator<Item = &'a T>>
+        = Map<Zip<TIter::IntoIter, vec::IntoIter<Scalar<T>>>, fn((&'a T, Scalar<T>)) -> T>
+    where
+        T: 'a,
+        Self: 'w;
+    fn windowed_dyn<'w, 'a, TIter: IntoIterator<Item = &'a T>>(
+        &'w self,
+        v: TIter,
+        len: usize,
+    ) -> Self::DynItemMapper<'w, 'a, TIter> {
+        v.into_iter()
+            .zip(self.coefficients(len))
+            .map(|(x, w)| *x * w)
+    }
+
+    fn window_in_place(&self, buffer: &mut [T]) {
+        let coefficients = self.coefficients::<Scalar<T>>(buffer.len());
+        for (x, w) in buffer.iter_mut().zip(coefficients) {
+            *x = *x * w;
+        }
+    }
+}
+
+/// Symmetric Dolph-Chebyshev window of `len` samples with sidelobes `attenuation_db` dB
+/// below its peak of 1.
+pub fn chebyshev_coefficients(attenuation_db: f64, len: usize) -> Vec<f64> {
+    if len <= 2 {
+        return vec![1.0; len];
+    }
+
+    // Chebyshev polynomial of the window order, sampled around the unit circle
+    let order = scalar::<f64>(len - 1);
+    let beta = Float::cosh(Float::acosh(Float::powf(10.0, attenuation_db / 20.0)) / order);
+    let odd = len % 2 == 1;
+    let spectrum = (0..len)
+        .map(|k| {
+            let x = beta * Float::cos(f64::PI() * scalar::<f64>(k) / scalar::<f64>(len));
+            let p = if x > 1.0 {
+                Float::cosh(order * Float::acosh(x))
+            } else if x < -1.0 {
+                let sign = if odd { 1.0 } else { -1.0 };
+                sign * Float::cosh(order * Float::acosh(-x))
+            } else {
+                Float::cos(order * Float::acos(x))
+            };
+            // Even lengths are centered between two samples, half a sample off the origin
+            if odd {
+                Complex64::new(p, 0.0)
+            } else {
+                Complex64::from_polar(p, -f64::PI() * scalar::<f64>(k) / scalar::<f64>(len))
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let engine = DynEngine::<Complex64, MixedRadix, Rect>::new(len)
+        .expect("MixedRadix supports every non-zero length");
+    let mut samples = vec![Complex64::default(); len];
+    engine
+        .ifft(&spectrum, &mut samples, Scaling::Unnormalized)
+        .expect("buffers are len samples long");
+
+    // The transform holds the second half of the window, mirror it around the center
+    let half = if odd { len.div_ceil(2) } else { len / 2 + 1 };
+    let first = if odd { 0 } else { 1 };
+    let window = samples[1..half]
+        .iter()
+        .rev()
+        .chain(samples[first..half].iter())
+        .map(|x| x.re)
+        .collect::<Vec<_>>();
+    let peak = window.iter().fold(f64::MIN, |max, w| max.max(*w));
+    window.into_iter().map(|w| w / peak).collect()
+}
+
+#[cfg(test)]
+mod test {
+    use approx::assert_relative_eq;
+
+    use crate::{
+        windows::{metrics::WindowMetrics, Symmetric},
+        WindowFunction,
+    };
+
+    use super::{chebyshev_coefficients, Chebyshev};
+
+    const N: usize = 16;
+
+    #[test]
+    fn chebyshev_test() {
+        // Reference values from the algorithm of scipy.signal.windows.chebwin, evaluated
+        // with a direct DFT
+        let expected = [
+            0.11169109836363068,
+            0.4196299892443339,
+            0.8137735925687217,
+            1.0,
+            0.8137735925687217,
+            0.4196299892443339,
+            0.11169109836363068,
+        ];
+        for (w, e) in chebyshev_coefficients(50.0, 7).iter().zip(expected.iter()) {
+            assert_relative_eq!(*w, *e, epsilon = 1e-12);
+        }
+        let expected = [
+            0.06847555416399563,
+            0.30321916165520113,
+            0.6868466207739321,
+            1.0,
+            1.0,
+            0.6868466207739321,
+            0.30321916165520113,
+            0.06847555416399563,
+        ];
+        for (w, e) in chebyshev_coefficients(60.0, 8).iter().zip(expected.iter()) {
+            assert_relative_eq!(*w, *e, epsilon = 1e-12);
+        }
+
+        // Every sidelobe reaches the requested level, including at prime lengths
+        for (len, attenuation_db) in [(31, 60.0), (64, 100.0), (101, 80.0)] {
+            let window = Chebyshev::<Symmetric>::new(attenuation_db);
+            assert_relative_eq!(
+                window.highest_sidelobe_db(len).unwrap(),
+                -attenuation_db,
+                epsilon = 0.05
+            );
+        }
+
+        let mut window = [1.0; N];
+        <Chebyshev>::new(80.0).window_in_place(&mut window);
+        let longer = chebyshev_coefficients(80.0, N + 1);
+        assert_eq!(window.as_slice(), &longer[..N]);
+        let windowed = <Chebyshev>::new(80.0)
+            .windowed::<N, _>(&[1.0; N])
+            .collect::<Vec<f64>>();
+        let windowed_dyn = <Chebyshev>::new(80.0)
+            .windowed_dyn(&[1.0; N], N)
+            .collect::<Vec<f64>>();
+        assert_eq!(windowed.as_slice(), window.as_slice());
+        assert_eq!(windowed_dyn.as_slice(), window.as_slice());
+    }
+}
@@ -0,0 +1,63 @@
+use core::{
+    iter::{Enumerate, Map, Repeat, Zip},
+    marker::PhantomData,
+    ops::Mul,
+};
+
+use num_complex::ComplexFloat;
+use num_traits::{Float, FloatConst};
+
+use crate::{scalar, Scalar, WindowFunction};
+
+use super::{Periodic, Symmetry};
+
+/// Cosine (sine) window `sin(πi/n)`, the square root of [`super::hanning::Hanning`].
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Cosine<S: Symmetry = Periodic>(PhantomData<S>);
+impl<T, S> WindowFunction<T> for Cosine<S>
+where
+    S: Symmetry,
+    T: Copy + ComplexFloat + Mul<Scalar<T>, Output = T>,
+{
+    type ItemMapper<'w, 'a, TIter: IntoIterator<Item = &'a T>>
+        = Map<Enumerate<TIter::IntoIter>, fn((usize, &'a T)) -> T>
+    where
+        T: 'a,
+        Self: 'w;
+    fn windowed<'w, 'a, const N: usize, TIter: IntoIterator<Item = &'a T>>(
+        &'w self,
+        v: TIter,
+    ) -> Self::ItemMapper<'w, 'a, TIter> {
+        v.into_iter()
+            .enumerate()
+            .map(|(i, x)| *x * cosine::<Scalar<T>>(i, S::period(N)))
+    }
+
+    type DynItemMapper<'w, 'a, TIter: IntoIterator<Item = &'a T>>
+        = Map<Zip<Enumerate<TIter::IntoIter>, Repeat<usize>>, fn(((usize, &'a T), usize)) -> T>
+    where
+        T: 'a,
+        Self: 'w;
+    fn windowed_dyn<'w, 'a, TIter: IntoIterator<Item = &'a T>>(
+        &'w self,
+        v: TIter,
+        len: usize,
+    ) -> Self::DynItemMapper<'w, 'a, TIter> {
+        v.into_iter()
+            .enumerate()
+            .zip(core::iter::repeat(len))
+            .map(|((i, x), len)| *x * cosine::<Scalar<T>>(i, S::period(len)))
+    }
+
+    fn window_in_place(&self, buffer: &mut [T]) {
+        let n = S::period(buffer.len());
+        for (i, x) in buffer.iter_mut().enumerate() {
+            *x = *x * cosine::<Scalar<T>>(i, n);
+        }
+    }
+}
+
+#[inline(always)]
+fn cosine<R: Float + FloatConst>(i: usize, n: usize) -> R {
+    (R::PI() * scalar(i) / scalar(n)).sin()
+}
@@ -0,0 +1,186 @@
+use core::{
+    iter::{Enumerate, Map, Repeat, Zip},
+    ops::Mul,
+};
+
+use num_complex::ComplexFloat;
+use num_traits::{Float, FloatConst};
+
+use crate::{scalar, Scalar, WindowFunction};
+
+use super::Symmetry;
+
+/// Generalized cosine-sum window `w[i] = a0 - a1 cos(2πi/n) + a2 cos(4πi/n) - ...`.
+///
+/// Implementors only list their coefficients and get [`WindowFunction`] for free.
+pub trait CosineSum {
+    /// Periodic or symmetric form, see [`super::Symmetry`].
+    type Symmetry: Symmetry;
+    const COEFFICIENTS: &'static [f64];
+}
+
+impl<T, W> WindowFunction<T> for W
+where
+    W: CosineSum,
+    T: Copy + ComplexFloat + Mul<Scalar<T>, Output = T>,
+{
+    type ItemMapper<'w, 'a, TIter: IntoIterator<Item = &'a T>>
+        = Map<Enumerate<TIter::IntoIter>, fn((usize, &'a T)) -> T>
+    where
+        T: 'a,
+        Self: 'w;
+    fn windowed<'w, 'a, const N: usize, TIter: IntoIterator<Item = &'a T>>(
+        &'w self,
+        v: TIter,
+    ) -> Self::ItemMapper<'w, 'a, TIter> {
+        v.into_iter()
+            .enumerate()
+            .map(|(i, x)| *x * cosine_sum::<Scalar<T>>(W::COEFFICIENTS, i, W::Symmetry::period(N)))
+    }
+
+    type DynItemMapper<'w, 'a, TIter: IntoIterator<Item = &'a T>>
+        = Map<Zip<Enumerate<TIter::IntoIter>, Repeat<usize>>, fn(((usize, &'a T), usize)) -> T>
+    where
+        T: 'a,
+        Self: 'w;
+    fn windowed_dyn<'w, 'a, TIter: IntoIterator<Item = &'a T>>(
+        &'w self,
+        v: TIter,
+        len: usize,
+    ) -> Self::DynItemMapper<'w, 'a, TIter> {
+        v.into_iter()
+            .enumerate()
+            .zip(core::iter::repeat(len))
+            .map(|((i, x), len)| {
+                *x * cosine_sum::<Scalar<T>>(W::COEFFICIENTS, i, W::Symmetry::period(len))
+            })
+    }
+
+    fn window_in_place(&self, buffer: &mut [T]) {
+        let n = W::Symmetry::period(buffer.len());
+        for (i, x) in buffer.iter_mut().enumerate() {
+            *x = *x * cosine_sum::<Scalar<T>>(W::COEFFICIENTS, i, n);
+        }
+    }
+}
+
+#[inline(always)]
+pub(crate) fn cosine_sum<R: Float + FloatConst>(coefficients: &[f64], i: usize, n: usize) -> R {
+    let phase = scalar::<R>(2) * R::PI() * scalar(i) / scalar(n);
+    coefficients
+        .iter()
+        .enumerate()
+        .fold(R::zero(), |acc, (k, a)| {
+            let term = scalar::<R>(*a) * (scalar::<R>(k) * phase).cos();
+            if k & 1 == 0 {
+                acc + term
+            } else {
+                acc - term
+            }
+        })
+}
+
+#[cfg(test)]
+mod test {
+    use approx::assert_relative_eq;
+
+    use num_complex::Complex64;
+
+    use crate::{
+        allocators::array::ArrayAllocator,
+        implementations::Naive,
+        windows::{
+            blackman::Blackman, blackman_harris::BlackmanHarris, blackman_nuttall::BlackmanNuttall,
+            flat_top::FlatTop, nuttall::Nuttall,
+        },
+        Engine, WindowFunction,
+    };
+
+    use super::CosineSum;
+
+    const N: usize = 16;
+
+    /// Indices of the samples checked against the reference values.
+    const INDICES: [usize; 5] = [0, 2, 5, 7, 8];
+
+    /// Checks samples of the periodic window of N points against values evaluated
+    /// independently in double precision from the published coefficients (Harris 1978,
+    /// Nuttall 1981), then the other windowing paths against each other.
+    fn reference_test<W>(expected: [f64; 5])
+    where
+        W: CosineSum + WindowFunction<f64> + WindowFunction<Complex64> + Default,
+    {
+        let mut window = [1.0; N];
+        W::default().window_in_place(&mut window);
+        for (i, e) in INDICES.iter().zip(expected.iter()) {
+            assert_relative_eq!(window[*i], *e, epsilon = 1e-9);
+        }
+
+        // Periodic window: w[i] = w[N - i]
+        for i in 1..N / 2 {
+            assert_relative_eq!(window[i], window[N - i], epsilon = 1e-9);
+        }
+
+        let windowed = W::default()
+            .windowed::<N, _>(&[1.0; N])
+            .into_iter()
+            .collect::<Vec<_>>();
+        let windowed_dyn = W::default()
+            .windowed_dyn(&[1.0; N], N)
+            .into_iter()
+            .collect::<Vec<_>>();
+        assert_eq!(windowed.as_slice(), window.as_slice());
+        assert_eq!(windowed_dyn.as_slice(), window.as_slice());
+
+        // Engines precompute the same coefficients
+        let engine = Engine::<Complex64, N, Naive, W, ArrayAllocator>::new();
+        assert_eq!(
+            engine.window_table().coefficients(),
+            Some(window.as_slice())
+        );
+    }
+
+    #[test]
+    fn blackman_test() {
+        reference_test::<Blackman>([0.0, 0.066446609407, 0.554773173688, 0.938508308751, 1.0]);
+    }
+
+    #[test]
+    fn blackman_harris_test() {
+        reference_test::<BlackmanHarris>([
+            0.00006,
+            0.021735837019,
+            0.434919534204,
+            0.914240925466,
+            1.0,
+        ]);
+    }
+
+    #[test]
+    fn nuttall_test() {
+        reference_test::<Nuttall>([0.0, 0.020039357147, 0.428654371309, 0.912873955873, 1.0]);
+    }
+
+    #[test]
+    fn blackman_nuttall_test() {
+        // scipy.signal.windows.nuttall is this window, not Nuttall's continuous-derivative one
+        reference_test::<BlackmanNuttall>([
+            0.0003628,
+            0.025205566515,
+            0.444360497486,
+            0.916185585444,
+            1.0,
+        ]);
+    }
+
+    #[test]
+    fn flat_top_test() {
+        reference_test::<FlatTop>([
+            -0.000421051,
+            -0.026872193286,
+            0.101745415389,
+            0.828535276866,
+            1.000000003,
+        ]);
+    }
+}
@@ -0,0 +1,162 @@
+use core::{
+    iter::{Enumerate, Map, Repeat, Zip},
+    ops::Mul,
+};
+
+use num_complex::ComplexFloat;
+
+use crate::{Scalar, WindowFunction};
+
+/// Source of the coefficients of a [`CustomWindow`].
+pub trait Coefficients<R> {
+    /// Coefficient `i` of a window of `len` samples.
+    fn coefficient(&self, i: usize, len: usize) -> R;
+}
+
+impl<R: Copy> Coefficients<R> for &[R] {
+    fn coefficient(&self, i: usize, _len: usize) -> R {
+        self[i]
+    }
+}
+
+/// Closure computing coefficient `i` of a window of `len` samples, see
+/// [`CustomWindow::from_fn`].
+#[derive(Clone, Copy)]
+pub struct FromFn<F>(F);
+impl<R, F> Coefficients<R> for FromFn<F>
+where
+    F: Fn(usize, usize) -> R,
+{
+    fn coefficient(&self, i: usize, len: usize) -> R {
+        (self.0)(i, len)
+    }
+}
+
+/// Window whose coefficients are provided at run time, by a table or a closure.
+#[derive(Clone, Copy)]
+pub struct CustomWindow<C>(C);
+
+impl<'c, R: Copy> CustomWindow<&'c [R]> {
+    /// Window using `coefficients` as is.
+    ///
+    /// Applying it to more samples than `coefficients.len()` panics.
+    pub fn from_slice(coefficients: &'c [R]) -> CustomWindow<&'c [R]> {
+        CustomWindow(coefficients)
+    }
+}
+
+impl<F> CustomWindow<FromFn<F>> {
+    /// Window whose coefficient `i` out of `len` is `coefficient(i, len)`.
+    pub fn from_fn<R>(coefficient: F) -> CustomWindow<FromFn<F>>
+    where
+        F: Fn(usize, usize) -> R,
+    {
+        CustomWindow(FromFn(coefficient))
+    }
+}
+
+impl<T, C> WindowFunction<T> for CustomWindow<C>
+where
+    C: Coefficients<Scalar<T>>,
+    T: Copy + ComplexFloat + Mul<Scalar<T>, Output = T>,
+{
+    type ItemMapper<'w, 'a, TIter: IntoIterator<Item = &'a T>>
+        = Map<
+        Zip<Enumerate<TIter::IntoIter>, Repeat<&'w CustomWindow<C>>>,
+        fn(((usize, &'a T), &'w CustomWindow<C>)) -> T,
+    >
+    where
+        T: 'a,
+        Self: 'w;
+    fn windowed<'w, 'a, const N: usize, TIter: IntoIterator<Item = &'a T>>(
+        &'w self,
+        v: TIter,
+    ) -> Self::ItemMapper<'w, 'a, TIter> {
+        v.into_iter()
+            .enumerate()
+            .zip(core::iter::repeat(self))
+            .map(|((i, x), window)| *x * window.0.coefficient(i, N))
+    }
+
+    type DynItemMapper<'w, 'a, TIter: IntoIterator<Item = &'a T>>
+        = Map<
+        Zip<Enumerate<TIter::IntoIter>, Repeat<(usize, &'w CustomWindow<C>)>>,
+        fn(((usize, &'a T), (usize, &'w CustomWindow<C>))) -> T,
+    >
+    where
+        T: 'a,
+        Self: 'w;
+    fn windowed_dyn<'w, 'a, TIter: IntoIterator<Item = &'a T>>(
+        &'w self,
+        v: TIter,
+        len: usize,
+    ) -> Self::DynItemMapper<'w, 'a, TIter> {
+        v.into_iter()
+            .enumerate()
+            .zip(core::iter::repeat((len, self)))
+            .map(|((i, x), (len, window))| *x * window.0.coefficient(i, len))
+    }
+
+    fn window_in_place(&self, buffer: &mut [T]) {
+        let len = buffer.len();
+        for (i, x) in buffer.iter_mut().enumerate() {
+            *x = *x * self.0.coefficient(i, len);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use num_complex::Complex32;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    use crate::{
+        allocators::array::ArrayAllocator,
+        implementations::CooleyTukey,
+        test::array_assert_eq,
+        windows::{hanning::Hanning, Rect},
+        Engine, WindowFunction,
+    };
+
+    use super::CustomWindow;
+
+    const N: usize = 32;
+
+    #[test]
+    fn custom_window_test() {
+        let mut coefficients = [1.0f32; N];
+        <Hanning>::default().window_in_place(&mut coefficients);
+
+        let mut rng = StdRng::seed_from_u64(1234);
+        let v = (0..N).map(|_| rng.gen()).collect::<Vec<Complex32>>();
+        let mut expected = [Complex32::default(); N];
+        Engine::<Complex32, N, CooleyTukey, Hanning, ArrayAllocator>::new().fft(&v, &mut expected);
+
+        let slice_engine = Engine::<Complex32, N, CooleyTukey, _, ArrayAllocator>::with_window(
+            CustomWindow::from_slice(&coefficients),
+        );
+        let mut spectrum = [Complex32::default(); N];
+        slice_engine.fft(&v, &mut spectrum);
+        array_assert_eq(&expected, &spectrum, 1e-5);
+
+        let fn_engine = Engine::<Complex32, N, CooleyTukey, _, ArrayAllocator>::with_window(
+            CustomWindow::from_fn(|i, len| {
+                0.5 - 0.5 * (2.0 * core::f32::consts::PI * i as f32 / len as f32).cos()
+            }),
+        );
+        fn_engine.fft(&v, &mut spectrum);
+        array_assert_eq(&expected, &spectrum, 1e-5);
+
+        // Same coefficients whether the length is known at compile time or not
+        let windowed = slice_engine
+            .window()
+            .windowed::<N, _>(&v)
+            .collect::<Vec<_>>();
+        let windowed_dyn = fn_engine.window().windowed_dyn(&v, N).collect::<Vec<_>>();
+        array_assert_eq(windowed.as_slice(), windowed_dyn.as_slice(), 1e-6);
+
+        // Parameterless windows stay zero-sized
+        assert_eq!(core::mem::size_of::<Rect>(), 0);
+        assert_eq!(core::mem::size_of::<Hanning>(), 0);
+    }
+}
@@ -0,0 +1,458 @@
+use core::{
+    iter::{Map, Zip},
+    marker::PhantomData,
+    ops::Mul,
+};
+
+use alloc::vec::{self, Vec};
+use num_complex::{Complex64, ComplexFloat};
+use num_traits::{Float, FloatConst};
+
+use crate::{implementations::MixedRadix, scalar, DynEngine, Scalar, Scaling, WindowFunction};
+
+use super::{custom::CustomWindow, Periodic, Rect, Symmetry};
+
+/// Inverse iteration steps per taper, each one gaining several digits with well separated
+/// eigenvalues.
+const INVERSE_ITERATIONS: usize = 4;
+
+/// Discrete prolate spheroidal (Slepian) taper `index` for the time-halfbandwidth product
+/// `nw`, with unit energy.
+///
+/// Taper 0 is the sequence most concentrated in the band `|f| < nw / len`, each next one
+/// being the most concentrated sequence orthogonal to the previous ones. Coefficients are
+/// computed for each application, use [`dpss`] to get several tapers at once.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Dpss<S: Symmetry = Periodic> {
+    nw: f64,
+    index: usize,
+    symmetry: PhantomData<S>,
+}
+
+impl<S: Symmetry> Dpss<S> {
+    /// Slepian taper `index` for the time-halfbandwidth product `nw`.
+    ///
+    /// # Panics
+    /// Panics if `nw` is not positive. It must also be below half the window length, which
+    /// is only known, and checked, when the window is applied.
+    pub fn new(nw: f64, index: usize) -> Dpss<S> {
+        assert!(nw > 0.0, "nw must be positive, got {nw}");
+        Dpss {
+            nw,
+            index,
+            symmetry: PhantomData,
+        }
+    }
+
+    pub fn nw(&self) -> f64 {
+        self.nw
+    }
+
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    fn coefficients<R: Float>(&self, len: usize) -> Vec<R> {
+        // A periodic taper is the symmetric taper one sample longer, without its last sample
+        let mut tapers = dpss(S::period(len) + 1, self.nw, self.index + 1).tapers;
+        let mut coefficients = tapers.swap_remove(self.index);
+        coefficients.truncate(len);
+        coefficients.into_iter().map(scalar).collect()
+    }
+}
+
+impl<T, S> WindowFunction<T> for Dpss<S>
+where
+    S: Symmetry,
+    T: Copy + ComplexFloat + Mul<Scalar<T>, Output = T>,
+{
+    type ItemMapper<'w, 'a, TIter: IntoIterator<Item = &'a T>>
+        = Map<Zip<TIter::IntoIter, vec::IntoIter<Scalar<T>>>, fn((&'a T, Scalar<T>)) -> T>
+    where
+        T: 'a,
+        Self: 'w;
+    fn windowed<'w, 'a, const N: usize, TIter: IntoIterator<Item = &'a T>>(
+        &'w self,
+        v: TIter,
+    ) -> Self::ItemMapper<'w, 'a, TIter> {
+        v.into_iter().zip(self.coefficients(N)).map(|(x, w)| *x * w)
+    }
+
+    type DynItemMapper<'w, 'a, TIter: IntoIterator<Item = &'a T>>
+        = Map<Zip<TIter::IntoIter, vec::IntoIter<Scalar<T>>>, fn((&'a T, Scalar<T>)) -> T>
+    where
+        T: 'a,
+        Self: 'w;
+    fn windowed_dyn<'w, 'a, TIter: IntoIterator<Item = &'a T>>(
+        &'w self,
+        v: TIter,
+        len: usize,
+    ) -> Self::DynItemMapper<'w, 'a, TIter> {
+        v.into_iter()
+            .zip(self.coefficients(len))
+            .map(|(x, w)| *x * w)
+    }
+
+    fn window_in_place(&self, buffer: &mut [T]) {
+        let coefficients = self.coefficients::<Scalar<T>>(buffer.len());
+        for (x, w) in buffer.iter_mut().zip(coefficients) {
+            *x = *x * w;
+        }
+    }
+}
+
+/// Set of orthogonal Slepian tapers, see [`dpss`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct DpssTapers {
+    tapers: Vec<Vec<f64>>,
+    concentrations: Vec<f64>,
+}
+
+impl DpssTapers {
+    /// Unit-energy tapers, from the most concentrated one.
+    pub fn tapers(&self) -> &[Vec<f64>] {
+        &self.tapers
+    }
+
+    /// Fraction of the energy of each taper falling in the band `|f| < nw / len`.
+    pub fn concentrations(&self) -> &[f64] {
+        &self.concentrations
+    }
+
+    /// Taper `index` as a window, for sequences of exactly `len` samples.
+    pub fn window(&self, index: usize) -> CustomWindow<&[f64]> {
+        CustomWindow::from_slice(&self.tapers[index])
+    }
+}
+
+/// First `count` symmetric discrete prolate spheroidal sequences of `len` samples for the
+/// time-halfbandwidth product `nw`, typically used with `count` up to `2 nw - 1`.
+///
+/// The tapers are the eigenvectors of the tridiagonal matrix commuting with the
+/// concentration problem, whose largest eigenvalues are isolated by Sturm sequence
+/// bisection before inverse iteration recovers their eigenvectors.
+///
+/// # Panics
+/// Panics if `count` is larger than `len`, or if `nw` is not in `(0, len / 2)`.
+pub fn dpss(len: usize, nw: f64, count: usize) -> DpssTapers {
+    assert!(count <= len, "cannot build {count} tapers of {len} samples");
+    assert!(
+        nw > 0.0 && nw < scalar::<f64>(len) / 2.0,
+        "nw must be in (0, {}), got {nw}",
+        len / 2
+    );
+
+    // Tridiagonal matrix whose eigenvectors are the Slepian sequences, `off[i]` linking
+    // rows i - 1 and i
+    let n = scalar::<f64>(len);
+    let bandwidth = nw / n;
+    let cos = Float::cos(2.0 * f64::PI() * bandwidth);
+    let diagonal = (0..len)
+        .map(|i| {
+            let center = (n - 1.0 - scalar::<f64>(2 * i)) / 2.0;
+            center * center * cos
+        })
+        .collect::<Vec<_>>();
+    let off = (0..len)
+        .map(|i| scalar::<f64>(i) * (n - scalar::<f64>(i)) / 2.0)
+        .collect::<Vec<_>>();
+
+    let mut tapers: Vec<Vec<f64>> = Vec::with_capacity(count);
+    for k in 0..count {
+        let eigenvalue = bisect_eigenvalue(&diagonal, &off, len - 1 - k);
+        let mut taper = (0..len)
+            .map(|i| 1.0 + scalar::<f64>(i) / n)
+            .collect::<Vec<_>>();
+        for _ in 0..INVERSE_ITERATIONS {
+            taper = solve_shifted(&diagonal, &off, eigenvalue, taper);
+            // Keep clear of the tapers already found, in case of close eigenvalues
+            for previous in tapers.iter() {
+                let projection = dot(&taper, previous);
+                for (x, p) in taper.iter_mut().zip(previous.iter()) {
+                    *x -= projection * p;
+                }
+            }
+            let norm = Float::sqrt(dot(&taper, &taper));
+            for x in taper.iter_mut() {
+                *x /= norm;
+            }
+        }
+
+        // Symmetric tapers sum to a positive value, antisymmetric ones start positive
+        let flip = if k % 2 == 0 {
+            taper.iter().sum::<f64>() < 0.0
+        } else {
+            let threshold = (1.0 / n).max(1e-7);
+            taper
+                .iter()
+                .find(|x| *x * *x > threshold)
+                .is_some_and(|x| *x < 0.0)
+        };
+        if flip {
+            for x in taper.iter_mut() {
+                *x = -*x;
+            }
+        }
+        tapers.push(taper);
+    }
+
+    let concentrations = tapers
+        .iter()
+        .map(|taper| concentration(taper, bandwidth))
+        .collect();
+    DpssTapers {
+        tapers,
+        concentrations,
+    }
+}
+
+/// Eigenvalue `index`, in ascending order, of the symmetric tridiagonal matrix.
+fn bisect_eigenvalue(diagonal: &[f64], off: &[f64], index: usize) -> f64 {
+    // Gershgorin bounds
+    let len = diagonal.len();
+    let radius = |i: usize| off[i].abs() + off.get(i + 1).map_or(0.0, |e| e.abs());
+    let mut low = (0..len).fold(f64::MAX, |min, i| min.min(diagonal[i] - radius(i)));
+    let mut high = (0..len).fold(f64::MIN, |max, i| max.max(diagonal[i] + radius(i)));
+
+    // Number of eigenvalues below x, from the signs of the Sturm sequence
+    let below = |x: f64| {
+        let mut count = 0;
+        let mut q = 1.0;
+        for i in 0..len {
+            let previous = if q == 0.0 { f64::EPSILON } else { q };
+            q = diagonal[i]
+                - x
+                - if i == 0 {
+                    0.0
+                } else {
+                    off[i] * off[i] / previous
+                };
+            if q < 0.0 {
+                count += 1;
+            }
+        }
+        count
+    };
+
+    let tolerance = f64::EPSILON * low.abs().max(high.abs());
+    while high - low > tolerance {
+        let middle = (low + high) / 2.0;
+        if middle <= low || middle >= high {
+            break;
+        }
+        if below(middle) <= index {
+            low = middle;
+        } else {
+            high = middle;
+        }
+    }
+    (low + high) / 2.0
+}
+
+/// Solves `(T - shift I) x = rhs` for the symmetric tridiagonal matrix `T`, through Gaussian
+/// elimination with partial pivoting (as LAPACK's gtsv). Near-zero pivots, expected with
+/// shifts on an eigenvalue, are nudged to keep the solve finite.
+fn solve_shifted(diagonal: &[f64], off: &[f64], shift: f64, mut rhs: Vec<f64>) -> Vec<f64> {
+    let len = diagonal.len();
+    let scale = diagonal
+        .iter()
+        .chain(off.iter())
+        .fold(0.0, |max: f64, x| max.max(x.abs()));
+    let tiny = f64::EPSILON * scale.max(1.0);
+    let mut d = diagonal.iter().map(|x| x - shift).collect::<Vec<_>>();
+    let mut upper = off[1..].to_vec();
+    let lower = off[1..].to_vec();
+    let mut fill = vec![0.0; len];
+
+    for i in 0..len - 1 {
+        if d[i].abs() >= lower[i].abs() {
+            if d[i].abs() < tiny {
+                d[i] = tiny;
+            }
+            let factor = lower[i] / d[i];
+            d[i + 1] -= factor * upper[i];
+            rhs[i + 1] -= factor * rhs[i];
+        } else {
+            // Swap rows i and i + 1, the latter bringing a second superdiagonal entry
+            let factor = d[i] / lower[i];
+            d[i] = lower[i];
+            let next = d[i + 1];
+            d[i + 1] = upper[i] - factor * next;
+            if i + 2 < len {
+                fill[i] = upper[i + 1];
+                upper[i + 1] = -factor * fill[i];
+            }
+            upper[i] = next;
+            rhs.swap(i, i + 1);
+            rhs[i + 1] -= factor * rhs[i];
+        }
+    }
+    if d[len - 1].abs() < tiny {
+        d[len - 1] = tiny;
+    }
+
+    for i in (0..len).rev() {
+        let mut x = rhs[i];
+        if i + 1 < len {
+            x -= upper[i] * rhs[i + 1];
+        }
+        if i + 2 < len {
+            x -= fill[i] * rhs[i + 2];
+        }
+        rhs[i] = x / d[i];
+    }
+    rhs
+}
+
+/// Energy of `taper` within `|f| < bandwidth`, from its autocorrelation computed with the
+/// crate's own transforms.
+fn concentration(taper: &[f64], bandwidth: f64) -> f64 {
+    let len = 2 * taper.len();
+    let engine = DynEngine::<Complex64, MixedRadix, Rect>::new(len)
+        .expect("MixedRadix supports every non-zero length");
+    let padded = taper
+        .iter()
+        .map(|x| Complex64::new(*x, 0.0))
+        .chain(core::iter::repeat(Complex64::default()))
+        .take(len)
+        .collect::<Vec<_>>();
+    let mut spectrum = vec![Complex64::default(); len];
+    engine
+        .fft(&padded, &mut spectrum)
+        .expect("buffers are len samples long");
+    let power = spectrum
+        .iter()
+        .map(|x| Complex64::new(x.norm_sqr(), 0.0))
+        .collect::<Vec<_>>();
+    let mut autocorrelation = vec![Complex64::default(); len];
+    engine
+        .ifft(&power, &mut autocorrelation, Scaling::Normalized)
+        .expect("buffers are len samples long");
+
+    // Integral of the power spectrum over the band, lag by lag
+    let sinc = |lag: usize| {
+        let lag = scalar::<f64>(lag);
+        Float::sin(2.0 * f64::PI() * bandwidth * lag) / (f64::PI() * lag)
+    };
+    2.0 * bandwidth * autocorrelation[0].re
+        + 2.0
+            * (1..taper.len())
+                .map(|lag| autocorrelation[lag].re * sinc(lag))
+                .sum::<f64>()
+}
+
+fn dot(a: &[f64], b: &[f64]) -> f64 {
+    a.iter().zip(b.iter()).map(|(x, y)| x * y).sum()
+}
+
+#[cfg(test)]
+mod test {
+    use core::f64::consts::PI;
+
+    use approx::assert_relative_eq;
+
+    use crate::{windows::Symmetric, WindowFunction};
+
+    use super::{dot, dpss, Dpss};
+
+    #[test]
+    fn dpss_test() {
+        let len = 64;
+        let nw = 4.0;
+        let set = dpss(len, nw, 7);
+
+        // Orthonormal, alternately symmetric and antisymmetric
+        for (k, taper) in set.tapers().iter().enumerate() {
+            for (j, other) in set.tapers().iter().enumerate() {
+                let expected = if j == k { 1.0 } else { 0.0 };
+                assert_relative_eq!(dot(taper, other), expected, epsilon = 1e-9);
+            }
+            let parity = if k % 2 == 0 { 1.0 } else { -1.0 };
+            for i in 0..len {
+                assert_relative_eq!(taper[i], parity * taper[len - 1 - i], epsilon = 1e-9);
+            }
+        }
+
+        // The first 2NW - 1 tapers keep nearly all their energy within the band, in
+        // decreasing order
+        let concentrations = set.concentrations();
+        assert!(concentrations[0] > 0.999_999_9);
+        assert!(concentrations[5] > 0.99);
+        assert!(concentrations[6] > 0.9 && concentrations[6] < 0.99);
+
+        // Same as the quadratic form of the concentration matrix
+        let bandwidth = nw / len as f64;
+        for (taper, concentration) in set.tapers().iter().zip(concentrations.iter()) {
+            let mut direct = 0.0;
+            for n in 0..len {
+                for m in 0..len {
+                    let lag = n as f64 - m as f64;
+                    let kernel = if n == m {
+                        2.0 * bandwidth
+                    } else {
+                        (2.0 * PI * bandwidth * lag).sin() / (PI * lag)
+                    };
+                    direct += taper[n] * taper[m] * kernel;
+                }
+            }
+            assert_relative_eq!(direct, *concentration, epsilon = 1e-12);
+        }
+        for pair in concentrations.windows(2) {
+            assert!(pair[0] > pair[1]);
+        }
+
+        // Each taper is the best concentrated sequence orthogonal to the previous ones:
+        // its concentration beats any other unit-energy mix of the later tapers
+        let concentration = |taper: &[f64]| super::concentration(taper, bandwidth);
+        let mix = set.tapers()[1]
+            .iter()
+            .zip(set.tapers()[2].iter())
+            .map(|(a, b)| (a + b) / 2f64.sqrt())
+            .collect::<Vec<_>>();
+        assert!(concentration(&mix) < concentrations[1]);
+
+        // Reference values from a dense Jacobi eigensolver on the same 8 x 8 matrix, NW = 2
+        let small = dpss(8, 2.0, 2);
+        let expected = [
+            [
+                0.049866986582568835,
+                0.1904720764793779,
+                0.3953020578405861,
+                0.5522408485414736,
+            ],
+            [
+                0.17263018791800652,
+                0.41946337594550415,
+                0.49384873246062,
+                0.2244164073922249,
+            ],
+        ];
+        for (taper, expected) in small.tapers().iter().zip(expected.iter()) {
+            for (w, e) in taper.iter().zip(expected.iter()) {
+                assert_relative_eq!(*w, *e, epsilon = 1e-12);
+            }
+        }
+
+        // As windows, symmetric tapers match the generated set
+        let mut window = vec![1.0; len];
+        Dpss::<Symmetric>::new(nw, 3).window_in_place(&mut window);
+        assert_eq!(window, set.tapers()[3]);
+        let windowed = set
+            .window(3)
+            .windowed_dyn(&vec![1.0; len], len)
+            .collect::<Vec<f64>>();
+        assert_eq!(windowed, set.tapers()[3]);
+    }
+
+    #[test]
+    #[should_panic(expected = "nw must be positive")]
+    fn zero_nw_test() {
+        <Dpss>::new(0.0, 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "nw must be positive")]
+    fn nan_nw_test() {
+        <Dpss>::new(f64::NAN, 0);
+    }
+}
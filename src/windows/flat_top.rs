@@ -0,0 +1,18 @@
+use core::marker::PhantomData;
+
+use super::{cosine_sum::CosineSum, Periodic, Symmetry};
+
+/// 5-term flat-top window, whose flat main lobe keeps amplitude errors of off-bin tones
+/// below 0.01 dB.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct FlatTop<S: Symmetry = Periodic>(PhantomData<S>);
+impl<S: Symmetry> CosineSum for FlatTop<S> {
+    type Symmetry = S;
+    const COEFFICIENTS: &'static [f64] = &[
+        0.21557895,
+        0.41663158,
+        0.277263158,
+        0.083578947,
+        0.006947368,
+    ];
+}
@@ -0,0 +1,161 @@
+use core::{
+    iter::{Enumerate, Map, Repeat, Zip},
+    marker::PhantomData,
+    ops::Mul,
+};
+
+use num_complex::ComplexFloat;
+use num_traits::Float;
+
+use crate::{scalar, Scalar, WindowFunction};
+
+use super::{Periodic, Symmetry};
+
+/// Gaussian window `exp(-((2i/n - 1) / σ)² / 2)`, with sigma relative to half the window
+/// length so the same window can be applied at any length.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Gaussian<S: Symmetry = Periodic> {
+    sigma: f64,
+    symmetry: PhantomData<S>,
+}
+
+impl<S: Symmetry> Gaussian<S> {
+    /// Gaussian window whose standard deviation is `sigma` times half its length.
+    ///
+    /// # Panics
+    /// Panics if `sigma` is not positive.
+    pub fn new(sigma: f64) -> Gaussian<S> {
+        assert!(sigma > 0.0, "sigma must be positive, got {sigma}");
+        Gaussian {
+            sigma,
+            symmetry: PhantomData,
+        }
+    }
+
+    pub fn sigma(&self) -> f64 {
+        self.sigma
+    }
+}
+
+impl<S: Symmetry> Default for Gaussian<S> {
+    /// Gaussian window with `sigma = 0.4`.
+    fn default() -> Gaussian<S> {
+        Gaussian::new(0.4)
+    }
+}
+
+impl<T, S> WindowFunction<T> for Gaussian<S>
+where
+    S: Symmetry,
+    T: Copy + ComplexFloat + Mul<Scalar<T>, Output = T>,
+{
+    type ItemMapper<'w, 'a, TIter: IntoIterator<Item = &'a T>>
+        = Map<
+        Zip<Enumerate<TIter::IntoIter>, Repeat<&'w Gaussian<S>>>,
+        fn(((usize, &'a T), &'w Gaussian<S>)) -> T,
+    >
+    where
+        T: 'a,
+        Self: 'w;
+    fn windowed<'w, 'a, const N: usize, TIter: IntoIterator<Item = &'a T>>(
+        &'w self,
+        v: TIter,
+    ) -> Self::ItemMapper<'w, 'a, TIter> {
+        v.into_iter()
+            .enumerate()
+            .zip(core::iter::repeat(self))
+            .map(|((i, x), window)| {
+                *x * gaussian::<Scalar<T>>(scalar(window.sigma), i, S::period(N))
+            })
+    }
+
+    type DynItemMapper<'w, 'a, TIter: IntoIterator<Item = &'a T>>
+        = Map<
+        Zip<Enumerate<TIter::IntoIter>, Repeat<(usize, &'w Gaussian<S>)>>,
+        fn(((usize, &'a T), (usize, &'w Gaussian<S>))) -> T,
+    >
+    where
+        T: 'a,
+        Self: 'w;
+    fn windowed_dyn<'w, 'a, TIter: IntoIterator<Item = &'a T>>(
+        &'w self,
+        v: TIter,
+        len: usize,
+    ) -> Self::DynItemMapper<'w, 'a, TIter> {
+        v.into_iter()
+            .enumerate()
+            .zip(core::iter::repeat((len, self)))
+            .map(|((i, x), (len, window))| {
+                *x * gaussian::<Scalar<T>>(scalar(window.sigma), i, S::period(len))
+            })
+    }
+
+    fn window_in_place(&self, buffer: &mut [T]) {
+        let n = S::period(buffer.len());
+        let sigma = scalar::<Scalar<T>>(self.sigma);
+        for (i, x) in buffer.iter_mut().enumerate() {
+            *x = *x * gaussian(sigma, i, n);
+        }
+    }
+}
+
+#[inline(always)]
+fn gaussian<R: Float>(sigma: R, i: usize, n: usize) -> R {
+    let ratio = (scalar::<R>(2 * i) / scalar(n) - R::one()) / sigma;
+    (-ratio * ratio / scalar(2)).exp()
+}
+
+#[cfg(test)]
+mod test {
+    use approx::assert_relative_eq;
+
+    use crate::{windows::Symmetric, WindowFunction};
+
+    use super::Gaussian;
+
+    const N: usize = 16;
+
+    #[test]
+    fn gaussian_test() {
+        // Reference values from scipy.signal.windows.gaussian(11, 2.0): sigma = 0.4 of the
+        // half length of 5 samples
+        let mut window = [1.0; 11];
+        Gaussian::<Symmetric>::new(0.4).window_in_place(&mut window);
+        let expected = [
+            0.04393693362340742,
+            0.1353352832366127,
+            0.32465246735834974,
+            0.6065306597126334,
+            0.8824969025845955,
+            1.0,
+        ];
+        for (i, &w) in expected.iter().enumerate() {
+            assert_relative_eq!(window[i], w, epsilon = 1e-12);
+            assert_relative_eq!(window[10 - i], w, epsilon = 1e-12);
+        }
+
+        let windowed = <Gaussian>::default()
+            .windowed::<N, _>(&[1.0; N])
+            .collect::<Vec<f64>>();
+        let windowed_dyn = <Gaussian>::default()
+            .windowed_dyn(&[1.0; N], N)
+            .collect::<Vec<f64>>();
+        let mut window = [1.0; N];
+        <Gaussian>::default().window_in_place(&mut window);
+        assert_relative_eq!(window[N / 2], 1.0);
+        assert_eq!(windowed.as_slice(), window.as_slice());
+        assert_eq!(windowed_dyn.as_slice(), window.as_slice());
+    }
+
+    #[test]
+    #[should_panic(expected = "sigma must be positive")]
+    fn zero_sigma_test() {
+        <Gaussian>::new(0.0);
+    }
+
+    #[test]
+    #[should_panic(expected = "sigma must be positive")]
+    fn negative_sigma_test() {
+        <Gaussian>::new(-0.4);
+    }
+}
@@ -1,29 +1,55 @@
 use core::{
-    iter::{Enumerate, Map},
+    iter::{Enumerate, Map, Repeat, Zip},
+    marker::PhantomData,
     ops::Mul,
 };
 
-#[allow(unused_imports)]
-use micromath::F32Ext;
+use num_complex::ComplexFloat;
+use num_traits::{Float, FloatConst};
 
-use crate::{Scalar, WindowFunction, PI};
+use crate::{scalar, Scalar, WindowFunction};
 
-pub struct Hanning;
-impl<T> WindowFunction<T> for Hanning
+use super::{Periodic, Symmetry};
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Hanning<S: Symmetry = Periodic>(PhantomData<S>);
+impl<T, S> WindowFunction<T> for Hanning<S>
 where
-    T: Copy + Mul<Scalar, Output = T>,
+    S: Symmetry,
+    T: Copy + ComplexFloat + Mul<Scalar<T>, Output = T>,
 {
-    type ItemMapper<'a, TIter : IntoIterator<Item = &'a T>> = Map<Enumerate<TIter::IntoIter>, fn((usize, &'a T)) -> T> where T : 'a;
-    fn windowed<'a, const N: usize, TIter: IntoIterator<Item = &'a T>>(
+    type ItemMapper<'w, 'a, TIter : IntoIterator<Item = &'a T>> = Map<Enumerate<TIter::IntoIter>, fn((usize, &'a T)) -> T> where T : 'a, Self: 'w;
+    fn windowed<'w, 'a, const N: usize, TIter: IntoIterator<Item = &'a T>>(
+        &'w self,
+        v: TIter,
+    ) -> Self::ItemMapper<'w, 'a, TIter> {
+        v.into_iter()
+            .enumerate()
+            .map(|(i, x)| *x * hanning::<Scalar<T>>(i, S::period(N)))
+    }
+
+    type DynItemMapper<'w, 'a, TIter: IntoIterator<Item = &'a T>> = Map<Zip<Enumerate<TIter::IntoIter>, Repeat<usize>>, fn(((usize, &'a T), usize)) -> T> where T : 'a, Self: 'w;
+    fn windowed_dyn<'w, 'a, TIter: IntoIterator<Item = &'a T>>(
+        &'w self,
         v: TIter,
-    ) -> Self::ItemMapper<'a, TIter> {
+        len: usize,
+    ) -> Self::DynItemMapper<'w, 'a, TIter> {
         v.into_iter()
             .enumerate()
-            .map(|(i, x)| *x * hanning(i as Scalar, N as Scalar))
+            .zip(core::iter::repeat(len))
+            .map(|((i, x), len)| *x * hanning::<Scalar<T>>(i, S::period(len)))
+    }
+
+    fn window_in_place(&self, buffer: &mut [T]) {
+        let n = S::period(buffer.len());
+        for (i, x) in buffer.iter_mut().enumerate() {
+            *x = *x * hanning::<Scalar<T>>(i, n);
+        }
     }
 }
 
 #[inline(always)]
-fn hanning(i: Scalar, n: Scalar) -> Scalar {
-    0.5 - 0.5 * (2.0 * PI * i / n).cos()
+fn hanning<R: Float + FloatConst>(i: usize, n: usize) -> R {
+    let phase = scalar::<R>(2) * R::PI() * scalar(i) / scalar(n);
+    scalar::<R>(0.5) - scalar::<R>(0.5) * phase.cos()
 }
@@ -0,0 +1,275 @@
+use core::{
+    iter::{Enumerate, Map, Repeat, Zip},
+    marker::PhantomData,
+    ops::Mul,
+};
+
+use num_complex::ComplexFloat;
+use num_traits::Float;
+
+use crate::{scalar, Scalar, WindowFunction};
+
+use super::{Periodic, Symmetry};
+
+/// Kaiser window shape parameter, set at the type level.
+pub trait KaiserBeta {
+    const BETA: f64;
+}
+
+/// Beta expressed in tenths, e.g. `Beta<86>` for beta = 8.6.
+pub struct Beta<const TENTHS: u32>;
+impl<const TENTHS: u32> KaiserBeta for Beta<TENTHS> {
+    const BETA: f64 = TENTHS as f64 / 10.0;
+}
+
+/// Kaiser window `I0(β sqrt(1 - (2i/n - 1)²)) / I0(β)` with beta fixed by `B`.
+///
+/// Larger betas trade a wider main lobe for lower sidelobes, see [`kaiser_beta`].
+pub struct Kaiser<B: KaiserBeta, S: Symmetry = Periodic>(PhantomData<(B, S)>);
+impl<B: KaiserBeta, S: Symmetry> Default for Kaiser<B, S> {
+    fn default() -> Kaiser<B, S> {
+        Kaiser(PhantomData)
+    }
+}
+
+impl<T, B, S> WindowFunction<T> for Kaiser<B, S>
+where
+    B: KaiserBeta,
+    S: Symmetry,
+    T: Copy + ComplexFloat + Mul<Scalar<T>, Output = T>,
+{
+    type ItemMapper<'w, 'a, TIter: IntoIterator<Item = &'a T>>
+        = Map<Enumerate<TIter::IntoIter>, fn((usize, &'a T)) -> T>
+    where
+        T: 'a,
+        Self: 'w;
+    fn windowed<'w, 'a, const N: usize, TIter: IntoIterator<Item = &'a T>>(
+        &'w self,
+        v: TIter,
+    ) -> Self::ItemMapper<'w, 'a, TIter> {
+        v.into_iter()
+            .enumerate()
+            .map(|(i, x)| *x * kaiser::<Scalar<T>>(scalar(B::BETA), i, S::period(N)))
+    }
+
+    type DynItemMapper<'w, 'a, TIter: IntoIterator<Item = &'a T>>
+        = Map<Zip<Enumerate<TIter::IntoIter>, Repeat<usize>>, fn(((usize, &'a T), usize)) -> T>
+    where
+        T: 'a,
+        Self: 'w;
+    fn windowed_dyn<'w, 'a, TIter: IntoIterator<Item = &'a T>>(
+        &'w self,
+        v: TIter,
+        len: usize,
+    ) -> Self::DynItemMapper<'w, 'a, TIter> {
+        v.into_iter()
+            .enumerate()
+            .zip(core::iter::repeat(len))
+            .map(|((i, x), len)| *x * kaiser::<Scalar<T>>(scalar(B::BETA), i, S::period(len)))
+    }
+
+    fn window_in_place(&self, buffer: &mut [T]) {
+        DynKaiser::<S>::new(B::BETA).window_in_place(buffer);
+    }
+}
+
+/// Kaiser window whose beta is only known at run time.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DynKaiser<S: Symmetry = Periodic> {
+    beta: f64,
+    symmetry: PhantomData<S>,
+}
+
+impl<S: Symmetry> DynKaiser<S> {
+    pub fn new(beta: f64) -> DynKaiser<S> {
+        DynKaiser {
+            beta,
+            symmetry: PhantomData,
+        }
+    }
+
+    /// Kaiser window reaching a sidelobe attenuation of `attenuation_db`, see [`kaiser_beta`].
+    pub fn from_attenuation(attenuation_db: f64) -> DynKaiser<S> {
+        DynKaiser::new(kaiser_beta(attenuation_db))
+    }
+
+    pub fn beta(&self) -> f64 {
+        self.beta
+    }
+}
+
+impl<T, S> WindowFunction<T> for DynKaiser<S>
+where
+    S: Symmetry,
+    T: Copy + ComplexFloat + Mul<Scalar<T>, Output = T>,
+{
+    type ItemMapper<'w, 'a, TIter: IntoIterator<Item = &'a T>>
+        = Map<
+        Zip<Enumerate<TIter::IntoIter>, Repeat<&'w DynKaiser<S>>>,
+        fn(((usize, &'a T), &'w DynKaiser<S>)) -> T,
+    >
+    where
+        T: 'a,
+        Self: 'w;
+    fn windowed<'w, 'a, const N: usize, TIter: IntoIterator<Item = &'a T>>(
+        &'w self,
+        v: TIter,
+    ) -> Self::ItemMapper<'w, 'a, TIter> {
+        v.into_iter()
+            .enumerate()
+            .zip(core::iter::repeat(self))
+            .map(|((i, x), window)| *x * kaiser::<Scalar<T>>(scalar(window.beta), i, S::period(N)))
+    }
+
+    type DynItemMapper<'w, 'a, TIter: IntoIterator<Item = &'a T>>
+        = Map<
+        Zip<Enumerate<TIter::IntoIter>, Repeat<(usize, &'w DynKaiser<S>)>>,
+        fn(((usize, &'a T), (usize, &'w DynKaiser<S>))) -> T,
+    >
+    where
+        T: 'a,
+        Self: 'w;
+    fn windowed_dyn<'w, 'a, TIter: IntoIterator<Item = &'a T>>(
+        &'w self,
+        v: TIter,
+        len: usize,
+    ) -> Self::DynItemMapper<'w, 'a, TIter> {
+        v.into_iter()
+            .enumerate()
+            .zip(core::iter::repeat((len, self)))
+            .map(|((i, x), (len, window))| {
+                *x * kaiser::<Scalar<T>>(scalar(window.beta), i, S::period(len))
+            })
+    }
+
+    fn window_in_place(&self, buffer: &mut [T]) {
+        let n = S::period(buffer.len());
+        let beta = scalar::<Scalar<T>>(self.beta);
+        for (i, x) in buffer.iter_mut().enumerate() {
+            *x = *x * kaiser(beta, i, n);
+        }
+    }
+}
+
+/// Beta of a Kaiser window whose highest sidelobe is `attenuation_db` dB below the main
+/// lobe (e.g. 60.0 for -60 dB sidelobes), from the fit of Kaiser and Schafer (1980).
+///
+/// Attenuations below the 13.26 dB of the rectangular window give beta = 0.
+pub fn kaiser_beta(attenuation_db: f64) -> f64 {
+    if attenuation_db > 60.0 {
+        0.12438 * (attenuation_db + 6.3)
+    } else if attenuation_db >= 13.26 {
+        let excess = attenuation_db - 13.26;
+        0.76609 * Float::powf(excess, 0.4) + 0.09834 * excess
+    } else {
+        0.0
+    }
+}
+
+/// Zeroth-order modified Bessel function of the first kind, through its power series.
+pub fn bessel_i0<R: Float>(x: R) -> R {
+    let half_x = x / scalar(2);
+    let mut term = R::one();
+    let mut sum = R::one();
+    // Terms peak around k = x / 2 and fall off quickly after, 500 is far past any useful beta
+    for k in 1..500 {
+        term = term * (half_x / scalar(k)) * (half_x / scalar(k));
+        sum = sum + term;
+        if term <= sum * R::epsilon() {
+            break;
+        }
+    }
+    sum
+}
+
+#[inline(always)]
+fn kaiser<R: Float>(beta: R, i: usize, n: usize) -> R {
+    let ratio = scalar::<R>(2 * i) / scalar(n) - R::one();
+    let arg = (R::one() - ratio * ratio).max(R::zero()).sqrt();
+    bessel_i0(beta * arg) / bessel_i0(beta)
+}
+
+#[cfg(test)]
+mod test {
+    use approx::assert_relative_eq;
+    use num_complex::Complex32;
+
+    use crate::{
+        allocators::array::ArrayAllocator, implementations::CooleyTukey,
+        windows::metrics::WindowMetrics, Engine, WindowFunction,
+    };
+
+    use super::{bessel_i0, kaiser_beta, Beta, DynKaiser, Kaiser};
+
+    const N: usize = 16;
+
+    #[test]
+    fn bessel_i0_test() {
+        assert_relative_eq!(bessel_i0(0.0), 1.0);
+        assert_relative_eq!(bessel_i0(1.0), 1.2660658777520082, max_relative = 1e-14);
+        assert_relative_eq!(bessel_i0(5.0), 27.23987182360445, max_relative = 1e-14);
+        assert_relative_eq!(bessel_i0(10.0), 2815.716628466255, max_relative = 1e-14);
+        assert_relative_eq!(bessel_i0(-5.0f32), 27.239872, max_relative = 1e-6);
+    }
+
+    #[test]
+    fn kaiser_beta_test() {
+        // The measured highest sidelobe lands close to the requested level
+        for attenuation_db in [20.0, 30.0, 45.0, 60.0, 80.0, 100.0] {
+            let sidelobe = <DynKaiser>::from_attenuation(attenuation_db)
+                .highest_sidelobe_db(256)
+                .unwrap();
+            assert_relative_eq!(sidelobe, -attenuation_db, epsilon = 0.5);
+        }
+        assert_eq!(kaiser_beta(10.0), 0.0);
+        assert_eq!(
+            <DynKaiser>::from_attenuation(60.0).beta(),
+            kaiser_beta(60.0)
+        );
+    }
+
+    #[test]
+    fn kaiser_window_test() {
+        let mut window = [1.0; N];
+        Kaiser::<Beta<86>>::default().window_in_place(&mut window);
+        assert_relative_eq!(window[N / 2], 1.0);
+        assert_relative_eq!(window[1], 0.0173516429160883, epsilon = 1e-12);
+        assert_relative_eq!(window[4], 0.3403936224401885, epsilon = 1e-12);
+        for i in 1..N / 2 {
+            assert_relative_eq!(window[i], window[N - i], epsilon = 1e-12);
+        }
+
+        // Runtime and type-level betas agree, and so do all the ways of applying the window
+        let mut dyn_window = [1.0; N];
+        <DynKaiser>::new(8.6).window_in_place(&mut dyn_window);
+        assert_eq!(window, dyn_window);
+        let windowed = Kaiser::<Beta<86>>::default()
+            .windowed::<N, _>(&[1.0; N])
+            .collect::<Vec<f64>>();
+        assert_eq!(windowed.as_slice(), window.as_slice());
+
+        // Beta = 0 is the rectangular window
+        let mut rect = [1.0; N];
+        Kaiser::<Beta<0>>::default().window_in_place(&mut rect);
+        assert_eq!(rect, [1.0; N]);
+    }
+
+    #[test]
+    fn kaiser_engine_test() {
+        let engine = Engine::<Complex32, N, CooleyTukey, Kaiser<Beta<86>>, ArrayAllocator>::new();
+        let mut spectrum = [Complex32::default(); N];
+        engine.fft(&[Complex32::new(1.0, 0.0); N], &mut spectrum);
+
+        let mut window = [1.0f32; N];
+        <DynKaiser>::new(8.6).window_in_place(&mut window);
+        assert_relative_eq!(spectrum[0].re, window.iter().sum::<f32>(), epsilon = 1e-5);
+
+        let dyn_engine =
+            Engine::<Complex32, N, CooleyTukey, DynKaiser, ArrayAllocator>::with_window(
+                DynKaiser::new(8.6),
+            );
+        let mut dyn_spectrum = [Complex32::default(); N];
+        dyn_engine.fft(&[Complex32::new(1.0, 0.0); N], &mut dyn_spectrum);
+        assert_eq!(spectrum, dyn_spectrum);
+    }
+}
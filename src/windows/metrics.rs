@@ -0,0 +1,274 @@
+#[cfg(feature = "alloc")]
+use core::marker::PhantomData;
+use core::ops::Mul;
+
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+use num_complex::{Complex64, ComplexFloat};
+use num_traits::{Float, FloatConst};
+
+use crate::{scalar, Scalar, WindowFunction};
+
+/// Frequency resolution, in points per bin, of the window spectrum searched for sidelobes.
+const OVERSAMPLING: usize = 16;
+
+/// Figures of merit of a window of `len` samples (see F. J. Harris, "On the use of windows
+/// for harmonic analysis with the discrete Fourier transform", 1978), and the spectrum
+/// normalizations built on them.
+///
+/// Implemented for every window, computed from its coefficients in double precision.
+pub trait WindowMetrics {
+    /// Mean of the coefficients: the factor by which the window scales an on-bin tone.
+    fn coherent_gain(&self, len: usize) -> f64;
+
+    /// Equivalent noise bandwidth in bins: width of the rectangular filter that lets
+    /// through as much white noise power as one bin of the window.
+    fn enbw(&self, len: usize) -> f64;
+
+    /// Gain in dB of the signal-to-noise ratio of a tone in white noise through one bin
+    /// of the transform, `10 log10(len / ENBW)`.
+    fn processing_gain_db(&self, len: usize) -> f64 {
+        10.0 * Float::log10(scalar::<f64>(len) / self.enbw(len))
+    }
+
+    /// Attenuation in dB of a tone halfway between two bins relative to an on-bin tone.
+    fn scalloping_loss_db(&self, len: usize) -> f64;
+
+    /// Level in dB of the highest sidelobe relative to the main lobe peak, or `None` if
+    /// the window spectrum has no sidelobe (e.g. windows of a couple of samples).
+    ///
+    /// The spectrum is evaluated at 16 points per bin, in O(len²).
+    fn highest_sidelobe_db(&self, len: usize) -> Option<f64>;
+
+    /// Factor making the magnitude of an on-bin complex tone read as its amplitude
+    /// (double it for the one-sided spectrum of a real tone).
+    fn amplitude_correction(&self, len: usize) -> f64 {
+        1.0 / (scalar::<f64>(len) * self.coherent_gain(len))
+    }
+
+    /// Factor making the sum of the squared magnitudes of the spectrum of broadband signals
+    /// match the energy of the signal.
+    fn energy_correction(&self, len: usize) -> f64;
+
+    /// Applies [`WindowMetrics::amplitude_correction`] to `spectrum`, its length being the
+    /// window length.
+    fn normalize_amplitude<T>(&self, spectrum: &mut [T])
+    where
+        T: Copy + ComplexFloat + Mul<Scalar<T>, Output = T>,
+    {
+        let correction = scalar::<Scalar<T>>(self.amplitude_correction(spectrum.len()));
+        for x in spectrum.iter_mut() {
+            *x = *x * correction;
+        }
+    }
+
+    /// Applies [`WindowMetrics::energy_correction`] to `spectrum`, its length being the
+    /// window length.
+    fn normalize_energy<T>(&self, spectrum: &mut [T])
+    where
+        T: Copy + ComplexFloat + Mul<Scalar<T>, Output = T>,
+    {
+        let correction = scalar::<Scalar<T>>(self.energy_correction(spectrum.len()));
+        for x in spectrum.iter_mut() {
+            *x = *x * correction;
+        }
+    }
+}
+
+impl<W> WindowMetrics for W
+where
+    W: WindowFunction<f64>,
+{
+    fn coherent_gain(&self, len: usize) -> f64 {
+        Coefficients::new(self, len).iter().sum::<f64>() / scalar::<f64>(len)
+    }
+
+    fn enbw(&self, len: usize) -> f64 {
+        let coefficients = Coefficients::new(self, len);
+        let sum = coefficients.iter().sum::<f64>();
+        let sum_squares = coefficients.iter().map(|w| w * w).sum::<f64>();
+        scalar::<f64>(len) * sum_squares / (sum * sum)
+    }
+
+    fn scalloping_loss_db(&self, len: usize) -> f64 {
+        let coefficients = Coefficients::new(self, len);
+        let peak = coefficients.response(0.0);
+        let half_bin = coefficients.response(0.5);
+        -20.0 * Float::log10(half_bin / peak)
+    }
+
+    fn highest_sidelobe_db(&self, len: usize) -> Option<f64> {
+        let coefficients = Coefficients::new(self, len);
+        let peak = coefficients.response(0.0);
+        let steps = len / 2 * OVERSAMPLING;
+        let at =
+            |step: usize| coefficients.response(scalar::<f64>(step) / scalar::<f64>(OVERSAMPLING));
+
+        // Walk down the main lobe to its first null, then keep the highest point past it
+        let mut previous = peak;
+        let mut step = 1;
+        while step <= steps {
+            let current = at(step);
+            if current > previous {
+                break;
+            }
+            previous = current;
+            step += 1;
+        }
+
+        (step..=steps)
+            .map(at)
+            .fold(None, |max: Option<f64>, x| {
+                Some(max.map_or(x, |max| max.max(x)))
+            })
+            .map(|sidelobe| 20.0 * Float::log10(sidelobe / peak))
+    }
+
+    fn energy_correction(&self, len: usize) -> f64 {
+        let coefficients = Coefficients::new(self, len);
+        1.0 / Float::sqrt(coefficients.iter().map(|w| w * w).sum::<f64>())
+    }
+}
+
+/// Coefficients of a window of `len` samples, computed once under `alloc` (Slepian and
+/// Chebyshev windows are costly to build) and on every pass otherwise.
+struct Coefficients<'w, W> {
+    #[cfg(feature = "alloc")]
+    values: Vec<f64>,
+    #[cfg(not(feature = "alloc"))]
+    window: &'w W,
+    #[cfg(feature = "alloc")]
+    window: PhantomData<&'w W>,
+    len: usize,
+}
+
+impl<'w, W: WindowFunction<f64>> Coefficients<'w, W> {
+    fn new(window: &'w W, len: usize) -> Coefficients<'w, W> {
+        Coefficients {
+            #[cfg(feature = "alloc")]
+            values: window
+                .windowed_dyn(core::iter::repeat_n(&1.0, len), len)
+                .into_iter()
+                .collect(),
+            #[cfg(not(feature = "alloc"))]
+            window,
+            #[cfg(feature = "alloc")]
+            window: PhantomData,
+            len,
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    fn iter(&self) -> impl Iterator<Item = f64> + '_ {
+        self.values.iter().copied()
+    }
+
+    #[cfg(not(feature = "alloc"))]
+    fn iter(&self) -> impl Iterator<Item = f64> + '_ {
+        self.window
+            .windowed_dyn(core::iter::repeat_n(&1.0, self.len), self.len)
+            .into_iter()
+    }
+
+    /// Magnitude of the spectrum of the window `bin` bins away from DC.
+    fn response(&self, bin: f64) -> f64 {
+        let step = -2.0 * f64::PI() * bin / scalar::<f64>(self.len);
+        self.iter()
+            .enumerate()
+            .fold(Complex64::new(0.0, 0.0), |acc, (n, w)| {
+                acc + Complex64::from_polar(w, step * scalar::<f64>(n))
+            })
+            .norm()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use approx::assert_relative_eq;
+    use num_complex::Complex32;
+
+    use crate::{
+        allocators::array::ArrayAllocator,
+        implementations::CooleyTukey,
+        windows::{
+            blackman_harris::BlackmanHarris, flat_top::FlatTop, hamming::Hamming, hanning::Hanning,
+            Rect,
+        },
+        Engine, WindowFunction,
+    };
+
+    use super::WindowMetrics;
+
+    const N: usize = 256;
+
+    /// Checks the metrics against the values tabulated by Harris.
+    fn metrics_test<W: WindowFunction<f64>>(
+        window: W,
+        coherent_gain: f64,
+        enbw: f64,
+        scalloping_loss_db: f64,
+        highest_sidelobe_db: f64,
+    ) {
+        assert_relative_eq!(window.coherent_gain(N), coherent_gain, epsilon = 1e-3);
+        assert_relative_eq!(window.enbw(N), enbw, epsilon = 1e-3);
+        assert_relative_eq!(
+            window.processing_gain_db(N),
+            10.0 * (N as f64 / enbw).log10(),
+            epsilon = 1e-2
+        );
+        assert_relative_eq!(
+            window.scalloping_loss_db(N),
+            scalloping_loss_db,
+            epsilon = 1e-2
+        );
+        assert_relative_eq!(
+            window.highest_sidelobe_db(N).unwrap(),
+            highest_sidelobe_db,
+            epsilon = 0.1
+        );
+    }
+
+    #[test]
+    fn harris_table_test() {
+        metrics_test(Rect, 1.0, 1.0, 3.92, -13.26);
+        metrics_test(<Hanning>::default(), 0.5, 1.5, 1.42, -31.47);
+        metrics_test(<Hamming>::default(), 0.54, 1.3628, 1.75, -42.68);
+        metrics_test(<BlackmanHarris>::default(), 0.35875, 2.0044, 0.83, -92.0);
+        assert!(<FlatTop>::default().scalloping_loss_db(N).abs() < 0.01);
+    }
+
+    #[test]
+    fn normalization_test() {
+        // An on-bin tone of amplitude 3 reads as 3 once amplitude corrected
+        let engine = Engine::<Complex32, N, CooleyTukey, Hanning, ArrayAllocator>::new();
+        let tone = (0..N)
+            .map(|n| {
+                Complex32::from_polar(
+                    3.0,
+                    2.0 * core::f32::consts::PI * 10.0 * n as f32 / N as f32,
+                )
+            })
+            .collect::<Vec<_>>();
+        let mut spectrum = [Complex32::default(); N];
+        engine.fft(&tone, &mut spectrum);
+        engine.window().normalize_amplitude(&mut spectrum);
+        assert_relative_eq!(spectrum[10].norm(), 3.0, epsilon = 1e-4);
+
+        // Energy correction preserves the energy of white noise, on average
+        let mut energy = 0.0;
+        let mut corrected = 0.0;
+        for seed in 0..64 {
+            use rand::{rngs::StdRng, Rng, SeedableRng};
+            let mut rng = StdRng::seed_from_u64(seed);
+            let noise = (0..N)
+                .map(|_| Complex32::new(rng.gen::<f32>() - 0.5, rng.gen::<f32>() - 0.5))
+                .collect::<Vec<_>>();
+            engine.fft(&noise, &mut spectrum);
+            engine.window().normalize_energy(&mut spectrum);
+            energy += noise.iter().map(|x| x.norm_sqr()).sum::<f32>();
+            corrected += spectrum.iter().map(|x| x.norm_sqr()).sum::<f32>();
+        }
+        assert_relative_eq!(corrected / energy, 1.0, epsilon = 2e-2);
+    }
+}
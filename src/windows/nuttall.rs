@@ -0,0 +1,11 @@
+use core::marker::PhantomData;
+
+use super::{cosine_sum::CosineSum, Periodic, Symmetry};
+
+/// 4-term Nuttall window with continuous first derivative (-93 dB sidelobes).
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Nuttall<S: Symmetry = Periodic>(PhantomData<S>);
+impl<S: Symmetry> CosineSum for Nuttall<S> {
+    type Symmetry = S;
+    const COEFFICIENTS: &'static [f64] = &[0.355768, 0.487396, 0.144232, 0.012604];
+}
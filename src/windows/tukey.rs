@@ -0,0 +1,175 @@
+use core::{
+    iter::{Enumerate, Map, Repeat, Zip},
+    marker::PhantomData,
+    ops::Mul,
+};
+
+use num_complex::ComplexFloat;
+use num_traits::{Float, FloatConst};
+
+use crate::{scalar, Scalar, WindowFunction};
+
+use super::{Periodic, Symmetry};
+
+/// Tukey (tapered cosine) window: flat in the middle, with cosine tapers covering a
+/// fraction `alpha` of its length, half on each side.
+///
+/// `alpha = 0` is [`super::Rect`] and `alpha = 1` is [`super::hanning::Hanning`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Tukey<S: Symmetry = Periodic> {
+    alpha: f64,
+    symmetry: PhantomData<S>,
+}
+
+impl<S: Symmetry> Tukey<S> {
+    /// Tukey window tapering a fraction `alpha` of its length, clamped to `[0, 1]` as
+    /// scipy does.
+    ///
+    /// # Panics
+    /// Panics if `alpha` is NaN.
+    pub fn new(alpha: f64) -> Tukey<S> {
+        assert!(!alpha.is_nan(), "alpha must be a number");
+        Tukey {
+            alpha: alpha.clamp(0.0, 1.0),
+            symmetry: PhantomData,
+        }
+    }
+
+    pub fn alpha(&self) -> f64 {
+        self.alpha
+    }
+}
+
+impl<S: Symmetry> Default for Tukey<S> {
+    /// Tukey window with `alpha = 0.5`.
+    fn default() -> Tukey<S> {
+        Tukey::new(0.5)
+    }
+}
+
+impl<T, S> WindowFunction<T> for Tukey<S>
+where
+    S: Symmetry,
+    T: Copy + ComplexFloat + Mul<Scalar<T>, Output = T>,
+{
+    type ItemMapper<'w, 'a, TIter: IntoIterator<Item = &'a T>>
+        = Map<
+        Zip<Enumerate<TIter::IntoIter>, Repeat<&'w Tukey<S>>>,
+        fn(((usize, &'a T), &'w Tukey<S>)) -> T,
+    >
+    where
+        T: 'a,
+        Self: 'w;
+    fn windowed<'w, 'a, const N: usize, TIter: IntoIterator<Item = &'a T>>(
+        &'w self,
+        v: TIter,
+    ) -> Self::ItemMapper<'w, 'a, TIter> {
+        v.into_iter()
+            .enumerate()
+            .zip(core::iter::repeat(self))
+            .map(|((i, x), window)| *x * tukey::<Scalar<T>>(scalar(window.alpha), i, S::period(N)))
+    }
+
+    type DynItemMapper<'w, 'a, TIter: IntoIterator<Item = &'a T>>
+        = Map<
+        Zip<Enumerate<TIter::IntoIter>, Repeat<(usize, &'w Tukey<S>)>>,
+        fn(((usize, &'a T), (usize, &'w Tukey<S>))) -> T,
+    >
+    where
+        T: 'a,
+        Self: 'w;
+    fn windowed_dyn<'w, 'a, TIter: IntoIterator<Item = &'a T>>(
+        &'w self,
+        v: TIter,
+        len: usize,
+    ) -> Self::DynItemMapper<'w, 'a, TIter> {
+        v.into_iter()
+            .enumerate()
+            .zip(core::iter::repeat((len, self)))
+            .map(|((i, x), (len, window))| {
+                *x * tukey::<Scalar<T>>(scalar(window.alpha), i, S::period(len))
+            })
+    }
+
+    fn window_in_place(&self, buffer: &mut [T]) {
+        let n = S::period(buffer.len());
+        let alpha = scalar::<Scalar<T>>(self.alpha);
+        for (i, x) in buffer.iter_mut().enumerate() {
+            *x = *x * tukey(alpha, i, n);
+        }
+    }
+}
+
+#[inline(always)]
+fn tukey<R: Float + FloatConst>(alpha: R, i: usize, n: usize) -> R {
+    let x = scalar::<R>(i) / scalar(n);
+    let edge = x.min(R::one() - x);
+    if edge >= alpha / scalar(2) {
+        R::one()
+    } else {
+        scalar::<R>(0.5) * (R::one() - (scalar::<R>(2) * R::PI() * edge / alpha).cos())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use approx::assert_relative_eq;
+
+    use crate::{
+        windows::{hanning::Hanning, Symmetric},
+        WindowFunction,
+    };
+
+    use super::Tukey;
+
+    const N: usize = 16;
+
+    #[test]
+    fn tukey_test() {
+        // Reference values from scipy.signal.windows.tukey(11, 0.5)
+        let mut window = [1.0; 11];
+        Tukey::<Symmetric>::new(0.5).window_in_place(&mut window);
+        let expected = [0.0, 0.3454915028125263, 0.9045084971874737, 1.0, 1.0, 1.0];
+        for (i, &w) in expected.iter().enumerate() {
+            assert_relative_eq!(window[i], w, epsilon = 1e-12);
+            assert_relative_eq!(window[10 - i], w, epsilon = 1e-12);
+        }
+
+        // Both ends of the alpha range
+        let mut rect = [1.0; N];
+        <Tukey>::new(0.0).window_in_place(&mut rect);
+        assert_eq!(rect, [1.0; N]);
+        let mut hann = [1.0; N];
+        <Tukey>::new(1.0).window_in_place(&mut hann);
+        let mut hanning = [1.0; N];
+        <Hanning>::default().window_in_place(&mut hanning);
+        for (t, h) in hann.iter().zip(hanning.iter()) {
+            assert_relative_eq!(*t, *h, epsilon = 1e-12);
+        }
+
+        // Out of range alphas are clamped to the nearest end
+        let mut clamped = [1.0; N];
+        <Tukey>::new(-0.5).window_in_place(&mut clamped);
+        assert_eq!(clamped, rect);
+        <Tukey>::new(1.5).window_in_place(&mut clamped);
+        assert_eq!(clamped, hann);
+        assert_eq!(<Tukey>::new(1.5).alpha(), 1.0);
+
+        let windowed = <Tukey>::default()
+            .windowed::<N, _>(&[1.0; N])
+            .collect::<Vec<f64>>();
+        let windowed_dyn = <Tukey>::default()
+            .windowed_dyn(&[1.0; N], N)
+            .collect::<Vec<f64>>();
+        let mut window = [1.0; N];
+        <Tukey>::default().window_in_place(&mut window);
+        assert_eq!(windowed.as_slice(), window.as_slice());
+        assert_eq!(windowed_dyn.as_slice(), window.as_slice());
+    }
+
+    #[test]
+    #[should_panic(expected = "alpha must be a number")]
+    fn nan_alpha_test() {
+        <Tukey>::new(f64::NAN);
+    }
+}
@@ -0,0 +1,64 @@
+use core::{
+    iter::{Enumerate, Map, Repeat, Zip},
+    marker::PhantomData,
+    ops::Mul,
+};
+
+use num_complex::ComplexFloat;
+use num_traits::Float;
+
+use crate::{scalar, Scalar, WindowFunction};
+
+use super::{Periodic, Symmetry};
+
+/// Parabolic window `1 - (2i/n - 1)²`.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Welch<S: Symmetry = Periodic>(PhantomData<S>);
+impl<T, S> WindowFunction<T> for Welch<S>
+where
+    S: Symmetry,
+    T: Copy + ComplexFloat + Mul<Scalar<T>, Output = T>,
+{
+    type ItemMapper<'w, 'a, TIter: IntoIterator<Item = &'a T>>
+        = Map<Enumerate<TIter::IntoIter>, fn((usize, &'a T)) -> T>
+    where
+        T: 'a,
+        Self: 'w;
+    fn windowed<'w, 'a, const N: usize, TIter: IntoIterator<Item = &'a T>>(
+        &'w self,
+        v: TIter,
+    ) -> Self::ItemMapper<'w, 'a, TIter> {
+        v.into_iter()
+            .enumerate()
+            .map(|(i, x)| *x * welch::<Scalar<T>>(i, S::period(N)))
+    }
+
+    type DynItemMapper<'w, 'a, TIter: IntoIterator<Item = &'a T>>
+        = Map<Zip<Enumerate<TIter::IntoIter>, Repeat<usize>>, fn(((usize, &'a T), usize)) -> T>
+    where
+        T: 'a,
+        Self: 'w;
+    fn windowed_dyn<'w, 'a, TIter: IntoIterator<Item = &'a T>>(
+        &'w self,
+        v: TIter,
+        len: usize,
+    ) -> Self::DynItemMapper<'w, 'a, TIter> {
+        v.into_iter()
+            .enumerate()
+            .zip(core::iter::repeat(len))
+            .map(|((i, x), len)| *x * welch::<Scalar<T>>(i, S::period(len)))
+    }
+
+    fn window_in_place(&self, buffer: &mut [T]) {
+        let n = S::period(buffer.len());
+        for (i, x) in buffer.iter_mut().enumerate() {
+            *x = *x * welch::<Scalar<T>>(i, n);
+        }
+    }
+}
+
+#[inline(always)]
+fn welch<R: Float>(i: usize, n: usize) -> R {
+    let ratio = scalar::<R>(2 * i) / scalar(n) - R::one();
+    R::one() - ratio * ratio
+}